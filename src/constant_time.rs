@@ -0,0 +1,379 @@
+use crate::fields::*;
+use num_bigint::{BigInt, Sign};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+// Arithmetic for secret values in fields with p < 2^128: two fixed 64-bit
+// limbs in Montgomery form (R = 2^128), no secret-dependent branches or
+// indexing, and inversion by Fermat's little theorem instead of xgcd.
+// Everything stored in CtField is derived from the public modulus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CtField {
+    modulus: [u64; 2],
+    // -p^-1 mod 2^64
+    inv: u64,
+    // R mod p and R^2 mod p
+    r: [u64; 2],
+    r2: [u64; 2],
+    p_minus_two: [u64; 2],
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CtFieldElement {
+    limbs: [u64; 2],
+    pub field: CtField,
+}
+
+impl CtField {
+    pub fn new(field: &Field) -> CtField {
+        assert!(
+            field.p.bits() <= 128 && field.p.bit(0),
+            "constant-time backend needs an odd modulus below 2^128"
+        );
+        let modulus = to_limbs(&field.p);
+
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(modulus[0].wrapping_mul(inv)));
+        }
+
+        CtField {
+            modulus,
+            inv: inv.wrapping_neg(),
+            r: to_limbs(&((BigInt::from(1) << 128) % field.p.clone())),
+            r2: to_limbs(&((BigInt::from(1) << 256) % field.p.clone())),
+            p_minus_two: to_limbs(&(field.p.clone() - BigInt::from(2))),
+        }
+    }
+
+    pub fn zero(&self) -> CtFieldElement {
+        CtFieldElement {
+            limbs: [0, 0],
+            field: *self,
+        }
+    }
+
+    pub fn one(&self) -> CtFieldElement {
+        CtFieldElement {
+            limbs: self.r,
+            field: *self,
+        }
+    }
+
+    // Subtracts p once if t = [t0, t1, t2] >= p; t must be below 2p.
+    fn reduce_once(&self, t: [u64; 3]) -> [u64; 2] {
+        let (r0, borrow) = sbb(t[0], self.modulus[0], 0);
+        let (r1, borrow) = sbb(t[1], self.modulus[1], borrow);
+        let (_, borrow) = sbb(t[2], 0, borrow);
+        [
+            (t[0] & borrow) | (r0 & !borrow),
+            (t[1] & borrow) | (r1 & !borrow),
+        ]
+    }
+
+    // CIOS Montgomery multiplication: a * b / R mod p.
+    fn montgomery_mul(&self, a: &[u64; 2], b: &[u64; 2]) -> [u64; 2] {
+        let mut t = [0u64; 3];
+        for b_i in b {
+            let (t0, carry) = mac(t[0], a[0], *b_i, 0);
+            let (t1, carry) = mac(t[1], a[1], *b_i, carry);
+            let (t2, t3) = adc(t[2], carry, 0);
+
+            let m = t0.wrapping_mul(self.inv);
+            let (_, carry) = mac(t0, m, self.modulus[0], 0);
+            let (t0, carry) = mac(t1, m, self.modulus[1], carry);
+            let (t1, carry) = adc(t2, carry, 0);
+            t = [t0, t1, t3 + carry];
+        }
+        self.reduce_once(t)
+    }
+}
+
+impl CtFieldElement {
+    // Conversion at the boundary goes through BigInt and is variable time:
+    // convert secrets once, before any secret-dependent work, and build the
+    // CtField once per field rather than per element.
+    pub fn from_field_element(
+        element: &FieldElement,
+        field: &CtField,
+    ) -> Result<CtFieldElement, &'static str> {
+        let p = from_limbs(&field.modulus);
+        if element.field.p != p {
+            return Err("element belongs to a different field");
+        }
+        if element.value < BigInt::ZERO || element.value >= p {
+            return Err("non-canonical field element");
+        }
+        Ok(CtFieldElement {
+            limbs: field.montgomery_mul(&to_limbs(&element.value), &field.r2),
+            field: *field,
+        })
+    }
+
+    // Variable time as well, see from_field_element.
+    pub fn to_field_element(self) -> FieldElement {
+        FieldElement::new(
+            from_limbs(&self.field.montgomery_mul(&self.limbs, &[1, 0])),
+            Field::new(from_limbs(&self.field.modulus)),
+        )
+    }
+
+    pub fn add(&self, right: &CtFieldElement) -> CtFieldElement {
+        let (s0, carry) = adc(self.limbs[0], right.limbs[0], 0);
+        let (s1, carry) = adc(self.limbs[1], right.limbs[1], carry);
+        CtFieldElement {
+            limbs: self.field.reduce_once([s0, s1, carry]),
+            field: self.field,
+        }
+    }
+
+    pub fn sub(&self, right: &CtFieldElement) -> CtFieldElement {
+        let (d0, borrow) = sbb(self.limbs[0], right.limbs[0], 0);
+        let (d1, borrow) = sbb(self.limbs[1], right.limbs[1], borrow);
+        let (d0, carry) = adc(d0, self.field.modulus[0] & borrow, 0);
+        let (d1, _) = adc(d1, self.field.modulus[1] & borrow, carry);
+        CtFieldElement {
+            limbs: [d0, d1],
+            field: self.field,
+        }
+    }
+
+    pub fn neg(&self) -> CtFieldElement {
+        self.field.zero().sub(self)
+    }
+
+    pub fn mul(&self, right: &CtFieldElement) -> CtFieldElement {
+        CtFieldElement {
+            limbs: self.field.montgomery_mul(&self.limbs, &right.limbs),
+            field: self.field,
+        }
+    }
+
+    pub fn square(&self) -> CtFieldElement {
+        self.mul(self)
+    }
+
+    // Montgomery ladder over all 128 exponent bits, swapping the two
+    // accumulators with a masked swap instead of branching on the bit.
+    pub fn pow(&self, exponent: &[u64; 2]) -> CtFieldElement {
+        let mut r0 = self.field.one();
+        let mut r1 = *self;
+        for i in (0..128).rev() {
+            let bit = Choice::from(((exponent[i / 64] >> (i % 64)) & 1) as u8);
+            CtFieldElement::conditional_swap(&mut r0, &mut r1, bit);
+            r1 = r0.mul(&r1);
+            r0 = r0.square();
+            CtFieldElement::conditional_swap(&mut r0, &mut r1, bit);
+        }
+        r0
+    }
+
+    // Fermat inversion, a^(p-2); zero maps to zero.
+    pub fn inverse(&self) -> CtFieldElement {
+        self.pow(&self.field.p_minus_two)
+    }
+
+    pub fn is_zero(&self) -> Choice {
+        self.ct_eq(&self.field.zero())
+    }
+}
+
+impl ConditionallySelectable for CtFieldElement {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        debug_assert_eq!(a.field, b.field);
+        CtFieldElement {
+            limbs: [
+                u64::conditional_select(&a.limbs[0], &b.limbs[0], choice),
+                u64::conditional_select(&a.limbs[1], &b.limbs[1], choice),
+            ],
+            field: a.field,
+        }
+    }
+}
+
+impl ConstantTimeEq for CtFieldElement {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        debug_assert_eq!(self.field, other.field);
+        self.limbs[0].ct_eq(&other.limbs[0]) & self.limbs[1].ct_eq(&other.limbs[1])
+    }
+}
+
+fn to_limbs(value: &BigInt) -> [u64; 2] {
+    let (sign, digits) = value.to_u64_digits();
+    assert!(
+        sign != Sign::Minus && digits.len() <= 2,
+        "value does not fit in two limbs"
+    );
+    [
+        digits.first().copied().unwrap_or(0),
+        digits.get(1).copied().unwrap_or(0),
+    ]
+}
+
+fn from_limbs(limbs: &[u64; 2]) -> BigInt {
+    let mut bytes = limbs[0].to_le_bytes().to_vec();
+    bytes.extend(limbs[1].to_le_bytes());
+    BigInt::from_bytes_le(Sign::Plus, &bytes)
+}
+
+// a + b + carry, returning (sum, carry).
+fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+// a - b - borrow, where borrow is 0 or all ones, returning (difference,
+// borrow) in the same convention.
+fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub(b as u128 + (borrow >> 63) as u128);
+    (t as u64, (t >> 64) as u64)
+}
+
+// a + b * c + carry, returning (low, high).
+fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 * c as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use std::time::Instant;
+
+    // |t| above this means the two input classes are distinguishable by timing
+    // with overwhelming confidence (the threshold used by dudect).
+    const DUDECT_THRESHOLD: f64 = 4.5;
+
+    // dudect-style leakage check: times `operation` on inputs drawn at random
+    // from a fixed class (`prepare(false)`) and a random class (`prepare(true)`),
+    // drops the slowest 10% of measurements as noise, and returns Welch's t
+    // statistic between the two timing distributions. All inputs are prepared
+    // before the first measurement so preparing them cannot skew the timings.
+    fn timing_leakage<T>(
+        samples: usize,
+        mut prepare: impl FnMut(bool) -> T,
+        mut operation: impl FnMut(&T),
+    ) -> f64 {
+        let mut rng = rand::thread_rng();
+        let inputs: Vec<(bool, T)> = (0..samples)
+            .map(|_| {
+                let class = rng.gen::<bool>();
+                (class, prepare(class))
+            })
+            .collect();
+        let measurements: Vec<(bool, f64)> = inputs
+            .iter()
+            .map(|(class, input)| {
+                let start = Instant::now();
+                operation(std::hint::black_box(input));
+                (*class, start.elapsed().as_nanos() as f64)
+            })
+            .collect();
+
+        let mut times: Vec<f64> = measurements.iter().map(|(_, t)| *t).collect();
+        times.sort_by(|a, b| a.total_cmp(b));
+        let cutoff = times[times.len() * 9 / 10];
+
+        // Welford running mean and variance per class
+        let mut stats = [(0.0_f64, 0.0_f64, 0.0_f64); 2];
+        for (class, t) in measurements.into_iter().filter(|(_, t)| *t <= cutoff) {
+            let (n, mean, m2) = &mut stats[class as usize];
+            *n += 1.0;
+            let delta = t - *mean;
+            *mean += delta / *n;
+            *m2 += delta * (t - *mean);
+        }
+
+        let [(n0, mean0, m20), (n1, mean1, m21)] = stats;
+        let variance = m20 / (n0 - 1.0) / n0 + m21 / (n1 - 1.0) / n1;
+        (mean0 - mean1) / variance.sqrt()
+    }
+
+    fn field() -> Field {
+        Field::main()
+    }
+
+    #[test]
+    fn matches_field_arithmetic() {
+        let mut rng = rand::thread_rng();
+        for field in [field(), Field::new(BigInt::from(0xffff_ffff_0000_0001u64))] {
+            let ct_field = CtField::new(&field);
+            let (mut a, mut b) = (field.random(&mut rng), field.random(&mut rng));
+            let ct_a = CtFieldElement::from_field_element(&a, &ct_field).unwrap();
+            let ct_b = CtFieldElement::from_field_element(&b, &ct_field).unwrap();
+            let exponent: [u64; 2] = rng.gen();
+            let big_exponent: BigInt =
+                (BigInt::from(exponent[1]) << 64) + BigInt::from(exponent[0]);
+
+            assert_eq!(ct_a.to_field_element(), a);
+            assert_eq!(ct_a.add(&ct_b).to_field_element(), a.add(&mut b));
+            assert_eq!(ct_a.sub(&ct_b).to_field_element(), a.sub(&mut b));
+            assert_eq!(ct_a.mul(&ct_b).to_field_element(), a.mul(&mut b));
+            assert_eq!(ct_a.neg().to_field_element(), a.neg());
+            assert_eq!(ct_a.inverse().to_field_element(), a.inverse());
+            assert_eq!(
+                ct_a.pow(&exponent).to_field_element(),
+                a.pow_vartime(&big_exponent.to_biguint().unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn from_field_element_checks_the_modulus() {
+        let ct_field = CtField::new(&field());
+        let goldilocks = Field::new(BigInt::from(0xffff_ffff_0000_0001u64));
+        assert!(matches!(
+            CtFieldElement::from_field_element(&goldilocks.one(), &ct_field),
+            Err("element belongs to a different field")
+        ));
+        // Agrees with the main field modulo 2^128, so comparing limbs alone
+        // would accept it.
+        let wide = Field::new(field().p + (BigInt::from(1) << 128));
+        assert!(matches!(
+            CtFieldElement::from_field_element(&wide.one(), &ct_field),
+            Err("element belongs to a different field")
+        ));
+
+        let p = field().p;
+        for value in [
+            p.clone(),
+            p.clone() + (BigInt::from(1) << 128),
+            BigInt::from(-1),
+        ] {
+            let element = FieldElement {
+                value,
+                field: field(),
+            };
+            assert!(matches!(
+                CtFieldElement::from_field_element(&element, &ct_field),
+                Err("non-canonical field element")
+            ));
+        }
+        let largest = FieldElement::new(p - 1, field());
+        let ct = CtFieldElement::from_field_element(&largest, &ct_field).unwrap();
+        assert_eq!(ct.to_field_element(), largest);
+    }
+
+    // Slow and sensitive to machine noise, so run it explicitly with
+    // `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn ladder_has_no_detectable_timing_leak() {
+        let ct_field = CtField::new(&field());
+        let element =
+            CtFieldElement::from_field_element(&field().random(&mut rand::thread_rng()), &ct_field)
+                .unwrap();
+        // fixed class: exponent zero, random class: uniformly random exponent
+        let exponent_class = |random: bool| -> [u64; 2] {
+            if random {
+                rand::thread_rng().gen()
+            } else {
+                [0, 0]
+            }
+        };
+        let t = timing_leakage(20000, exponent_class, |e| {
+            std::hint::black_box(element.pow(e));
+        });
+        assert!(t.abs() < DUDECT_THRESHOLD, "dudect t = {t:.2}");
+    }
+}
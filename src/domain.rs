@@ -0,0 +1,432 @@
+use crate::fields::*;
+use crate::polynomials::*;
+use crate::sparse::*;
+use num_bigint::BigInt;
+
+// Point sets the polynomial domain operations run over: either an arbitrary
+// list of points or an EvaluationDomain, whose structure gives the
+// zerofier in closed form.
+pub trait Domain {
+    fn points(&self) -> Vec<FieldElement>;
+    fn zerofier(&self) -> Polynomial;
+}
+
+impl Domain for [FieldElement] {
+    fn points(&self) -> Vec<FieldElement> {
+        self.to_vec()
+    }
+
+    fn zerofier(&self) -> Polynomial {
+        let field = self[0].field.clone();
+        let mut x = Polynomial::new([field.zero(), field.one()].to_vec());
+        let mut acc = Polynomial::new([field.one()].to_vec());
+        for d in self {
+            acc = acc.mul(&mut x.sub(&mut Polynomial::new([d.clone()].to_vec())));
+        }
+        acc
+    }
+}
+
+impl Domain for Vec<FieldElement> {
+    fn points(&self) -> Vec<FieldElement> {
+        self.as_slice().points()
+    }
+
+    fn zerofier(&self) -> Polynomial {
+        self.as_slice().zerofier()
+    }
+}
+
+// The coset offset * <generator> of a multiplicative subgroup of order
+// `size`; offset = 1 gives the subgroup itself. Element i is
+// offset * generator^i.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvaluationDomain {
+    pub size: usize,
+    pub generator: FieldElement,
+    pub offset: FieldElement,
+}
+
+impl EvaluationDomain {
+    pub fn new(field: &Field, size: usize) -> EvaluationDomain {
+        EvaluationDomain::coset(field, size, field.one())
+    }
+
+    pub fn coset(field: &Field, size: usize, offset: FieldElement) -> EvaluationDomain {
+        assert!(!offset.is_zero(), "coset offset must be non-zero");
+        EvaluationDomain {
+            size,
+            generator: field.primitive_nth_root(BigInt::from(size)),
+            offset,
+        }
+    }
+
+    pub fn field(&self) -> Field {
+        self.generator.field.clone()
+    }
+
+    pub fn element(&self, i: usize) -> FieldElement {
+        self.offset
+            .clone()
+            .mul(&mut self.generator.pow((i % self.size) as u64))
+    }
+
+    // The element one step along the generator, i.e. the next row of a trace
+    // laid out over the domain.
+    pub fn next(&self, i: usize) -> FieldElement {
+        self.element(i + 1)
+    }
+
+    pub fn elements(&self) -> impl Iterator<Item = FieldElement> + '_ {
+        let mut generator = self.generator.clone();
+        std::iter::successors(Some(self.offset.clone()), move |x| {
+            Some(x.clone().mul(&mut generator))
+        })
+        .take(self.size)
+    }
+
+    // All evaluations over the domain, by NTT when the size is a power of
+    // two and the polynomial fits: f(offset * x) is transformed over the
+    // subgroup.
+    pub fn evaluate(&self, polynomial: &Polynomial) -> Vec<FieldElement> {
+        if !self.size.is_power_of_two() || polynomial.coefficients.len() > self.size {
+            return polynomial.clone().evaluate_domain(self);
+        }
+        let mut coefficients = polynomial
+            .clone()
+            .scale(&mut self.offset.clone())
+            .coefficients;
+        coefficients.resize(self.size, self.field().zero());
+        ntt(&coefficients, &self.generator)
+    }
+
+    // Inverse of evaluate: the inverse NTT gives f(offset * x), which is
+    // scaled back by offset^-1.
+    pub fn interpolate(&self, values: &[FieldElement]) -> Polynomial {
+        assert_eq!(values.len(), self.size);
+        if !self.size.is_power_of_two() {
+            return Polynomial::interpolate_domain(self, &mut values.to_vec());
+        }
+        let mut inverse_size = self.inverse_size();
+        let coefficients = ntt(values, &self.generator.clone().inverse())
+            .iter_mut()
+            .map(|c| c.mul(&mut inverse_size))
+            .collect();
+        Polynomial::new(coefficients).scale(&mut self.offset.clone().inverse())
+    }
+
+    pub fn inverse_size(&self) -> FieldElement {
+        FieldElement::new(BigInt::from(self.size), self.field()).inverse()
+    }
+
+    // x^size - offset^size
+    fn vanishing_polynomial(&self) -> SparsePolynomial {
+        SparsePolynomial::monomial(self.size, self.field().one()).sub(&SparsePolynomial::monomial(
+            0,
+            self.offset.pow(self.size as u64),
+        ))
+    }
+
+    pub fn evaluate_vanishing(&self, x: &FieldElement) -> FieldElement {
+        self.vanishing().evaluate(x)
+    }
+
+    pub fn vanishing(&self) -> Zerofier {
+        Zerofier {
+            numerator: self.vanishing_polynomial(),
+            excluded: Vec::new(),
+        }
+    }
+
+    // Vanishes on every element but the last k, e.g. k = 1 for transition
+    // constraints that relate each row to the next one.
+    pub fn zerofier_except_last(&self, k: usize) -> Zerofier {
+        assert!(
+            k <= self.size,
+            "cannot exclude more elements than the domain has"
+        );
+        Zerofier {
+            numerator: self.vanishing_polynomial(),
+            excluded: (self.size - k..self.size)
+                .map(|i| self.element(i))
+                .collect(),
+        }
+    }
+
+    // The subset offset * <generator^factor> of size size / factor.
+    pub fn subdomain(&self, factor: usize) -> EvaluationDomain {
+        assert!(
+            factor > 0 && self.size.is_multiple_of(factor),
+            "factor must divide the domain size"
+        );
+        EvaluationDomain {
+            size: self.size / factor,
+            generator: self.generator.pow(factor as u64),
+            offset: self.offset.clone(),
+        }
+    }
+
+    // The coset of size size * factor with the same offset that contains
+    // this domain.
+    pub fn superdomain(&self, factor: usize) -> EvaluationDomain {
+        let larger =
+            EvaluationDomain::coset(&self.field(), self.size * factor, self.offset.clone());
+        assert!(
+            larger.generator.pow(factor as u64) == self.generator,
+            "domain generator is not a power of the field's root of unity"
+        );
+        larger
+    }
+
+    // Set inclusion. The multiplicative group is cyclic, so the subgroup of
+    // self is inside the subgroup of other exactly when its size divides
+    // other's; the offsets must then differ by an element of other's
+    // subgroup.
+    pub fn is_subdomain_of(&self, other: &EvaluationDomain) -> bool {
+        if self.field() != other.field() || !other.size.is_multiple_of(self.size) {
+            return false;
+        }
+        let offset_ratio = self.offset.clone().div(&mut other.offset.clone());
+        offset_ratio.pow(other.size as u64) == self.field().one()
+    }
+}
+
+impl Domain for EvaluationDomain {
+    fn points(&self) -> Vec<FieldElement> {
+        self.elements().collect()
+    }
+
+    fn zerofier(&self) -> Polynomial {
+        self.vanishing().to_polynomial()
+    }
+}
+
+// Radix-2 Cooley-Tukey transform over the subgroup generated by root, whose
+// order must be values.len(), a power of two.
+fn ntt(values: &[FieldElement], root: &FieldElement) -> Vec<FieldElement> {
+    let n = values.len();
+    if n == 1 {
+        return values.to_vec();
+    }
+    let root_squared = root.clone().mul(&mut root.clone());
+    let even: Vec<FieldElement> = values.iter().step_by(2).cloned().collect();
+    let odd: Vec<FieldElement> = values.iter().skip(1).step_by(2).cloned().collect();
+    let (even, mut odd) = (ntt(&even, &root_squared), ntt(&odd, &root_squared));
+
+    let mut result = vec![root.field.zero(); n];
+    let mut power = root.field.one();
+    for i in 0..n / 2 {
+        let mut t = power.mul(&mut odd[i]);
+        result[i] = even[i].clone().add(&mut t);
+        result[i + n / 2] = even[i].clone().sub(&mut t);
+        power = power.mul(&mut root.clone());
+    }
+    result
+}
+
+// numerator / prod (x - e) over the excluded points e, which must be roots
+// of the sparse numerator: for a coset, x^size - offset^size with a few of
+// its elements left out. Building and evaluating it costs O(log size) field
+// operations plus one per excluded point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Zerofier {
+    pub numerator: SparsePolynomial,
+    pub excluded: Vec<FieldElement>,
+}
+
+impl Zerofier {
+    pub fn evaluate(&self, x: &FieldElement) -> FieldElement {
+        let field = x.field.clone();
+        let denominator = |skip: Option<usize>| {
+            let mut acc = field.one();
+            for (i, e) in self.excluded.iter().enumerate() {
+                if Some(i) != skip {
+                    acc = acc.mul(&mut x.clone().sub(&mut e.clone()));
+                }
+            }
+            acc
+        };
+
+        // At an excluded point both sides vanish; the quotient there is the
+        // numerator's derivative over the other factors.
+        if let Some(i) = self.excluded.iter().position(|e| e == x) {
+            return self
+                .numerator
+                .derivative()
+                .evaluate(x)
+                .div(&mut denominator(Some(i)));
+        }
+        self.numerator.evaluate(x).div(&mut denominator(None))
+    }
+
+    pub fn to_polynomial(&self) -> Polynomial {
+        let mut acc = Polynomial::from(&self.numerator);
+        for e in self.excluded.iter() {
+            acc = acc.deep_quotient(&mut e.clone());
+        }
+        acc
+    }
+
+    // f / Z computed as f * prod (x - e) / numerator, so the dense zerofier
+    // is never built.
+    pub fn divide(&self, numerator: &mut Polynomial) -> Result<Polynomial, &'static str> {
+        let mut product = numerator.clone();
+        for e in self.excluded.iter() {
+            let mut factor = Polynomial::new([e.clone().neg(), e.field.one()].to_vec());
+            product = product.mul(&mut factor);
+        }
+        let (quotient, remainder) = product.divide_by_sparse(&self.numerator)?;
+        if !remainder.is_zero() {
+            return Err("polynomial does not vanish on the zerofier's domain");
+        }
+        Ok(quotient)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn goldilocks() -> Field {
+        Field::new(BigInt::from(0xffff_ffff_0000_0001u64))
+    }
+
+    // A power-of-two subgroup and coset of the main field, and a coset of
+    // size 12 in Goldilocks, which takes the non-NTT fallbacks.
+    fn domains() -> Vec<EvaluationDomain> {
+        let field = Field::main();
+        vec![
+            EvaluationDomain::new(&field, 16),
+            EvaluationDomain::coset(&field, 16, field.multiplicative_generator()),
+            EvaluationDomain::coset(&goldilocks(), 12, goldilocks().multiplicative_generator()),
+        ]
+    }
+
+    #[test]
+    fn elements_walk_the_coset() {
+        for domain in domains() {
+            let elements: Vec<FieldElement> = domain.elements().collect();
+            assert_eq!(elements.len(), domain.size);
+            for (i, x) in elements.iter().enumerate() {
+                assert_eq!(*x, domain.element(i));
+                assert_eq!(domain.next(i), domain.element((i + 1) % domain.size));
+            }
+            assert_eq!(
+                domain.generator.pow(domain.size as u64),
+                domain.field().one()
+            );
+            assert_eq!(
+                domain.inverse_size().mul(&mut FieldElement::new(
+                    BigInt::from(domain.size),
+                    domain.field()
+                )),
+                domain.field().one()
+            );
+        }
+    }
+
+    #[test]
+    fn evaluate_and_interpolate_match_the_point_list() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for domain in domains() {
+            let points = domain.points();
+            for degree in [0, domain.size / 2, domain.size - 1] {
+                let mut f = Polynomial::random(degree, &domain.field(), &mut rng);
+                let values = f.evaluate_domain(&points);
+                assert_eq!(domain.evaluate(&f), values);
+                assert_eq!(domain.interpolate(&values), f);
+                assert_eq!(
+                    Polynomial::interpolate_domain(&domain, &mut values.clone()),
+                    f
+                );
+            }
+            // Beyond the domain size evaluation falls back to Horner.
+            let mut f = Polynomial::random(domain.size + 3, &domain.field(), &mut rng);
+            assert_eq!(domain.evaluate(&f), f.evaluate_domain(&points));
+        }
+    }
+
+    #[test]
+    fn vanishing_polynomial_is_the_zerofier() {
+        for domain in domains() {
+            let dense = Polynomial::zerofier_domain(&domain.points());
+            assert_eq!(Polynomial::zerofier_domain(&domain), dense);
+            assert!(domain
+                .elements()
+                .all(|x| domain.evaluate_vanishing(&x).is_zero()));
+            let outside = domain
+                .offset
+                .clone()
+                .mul(&mut domain.field().multiplicative_generator());
+            assert!(!domain.evaluate_vanishing(&outside).is_zero());
+        }
+    }
+
+    #[test]
+    fn sub_and_superdomains() {
+        for domain in domains() {
+            let half = domain.subdomain(2);
+            assert_eq!(half.size, domain.size / 2);
+            assert!(half.is_subdomain_of(&domain));
+            assert!(!domain.is_subdomain_of(&half));
+            assert!(half.elements().all(|x| domain.points().contains(&x)));
+
+            let larger = domain.superdomain(4);
+            assert_eq!(larger.size, domain.size * 4);
+            assert!(domain.is_subdomain_of(&larger));
+            assert!(domain.elements().all(|x| larger.points().contains(&x)));
+            assert_eq!(larger.subdomain(4), domain);
+        }
+
+        let goldilocks_thirds = domains()[2].subdomain(3);
+        assert_eq!(goldilocks_thirds.size, 4);
+        assert!(goldilocks_thirds.is_subdomain_of(&domains()[2]));
+
+        // The subgroup and the coset of size 16 are disjoint.
+        let (subgroup, coset) = (&domains()[0], &domains()[1]);
+        assert!(!subgroup.is_subdomain_of(coset));
+        assert!(!coset.is_subdomain_of(subgroup));
+        assert!(subgroup.subdomain(2).is_subdomain_of(subgroup));
+        assert!(!subgroup.is_subdomain_of(&domains()[2]));
+    }
+
+    #[test]
+    fn zerofier_evaluates_like_its_dense_form() {
+        for domain in domains() {
+            for k in [0, 1, 3] {
+                let zerofier = domain.zerofier_except_last(k);
+                let mut dense =
+                    Polynomial::zerofier_domain(&domain.points()[..domain.size - k].to_vec());
+                assert_eq!(zerofier.to_polynomial(), dense);
+                // Includes the excluded points, where evaluate() switches to
+                // the numerator's derivative.
+                let outside = domain.field().multiplicative_generator();
+                for mut x in domain.elements().chain([outside]) {
+                    assert_eq!(zerofier.evaluate(&x), dense.evaluate(&mut x));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn zerofier_division() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for domain in domains() {
+            let zerofier = domain.zerofier_except_last(2);
+            let mut f = Polynomial::random(5, &domain.field(), &mut rng);
+            let mut multiple = zerofier.to_polynomial().mul(&mut f);
+            assert_eq!(zerofier.divide(&mut multiple), Ok(f.clone()));
+            assert_eq!(
+                domain.vanishing().divide(&mut Polynomial::new(Vec::new())),
+                Ok(Polynomial::new(Vec::new()))
+            );
+            assert!(zerofier.divide(&mut f).is_err());
+            assert!(domain
+                .vanishing()
+                .divide(&mut multiple.add(&mut Polynomial::new(vec![domain.field().one()])))
+                .is_err());
+        }
+    }
+}
@@ -1,4 +1,10 @@
-use num_bigint::BigInt;
+use num_bigint::{BigInt, BigUint, RandBigInt, Sign};
+use rand::distributions::Distribution;
+use rand::{Rng, RngCore};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
 
 pub fn xgcd(x: BigInt, y: BigInt) -> (BigInt, BigInt, BigInt) {
     let (mut old_r, mut r) = (x, y);
@@ -22,17 +28,263 @@ fn update_step(a: &mut BigInt, old_a: &mut BigInt, q: BigInt) {
     *old_a = temp;
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+// Distinct prime factors of n in ascending order: trial division strips the
+// small primes, Pollard's rho splits whatever composite part is left.
+pub fn prime_factors(n: &BigInt) -> Vec<BigInt> {
+    let mut factors = Vec::new();
+    let mut rest = n.clone();
+    let mut d = BigInt::from(2);
+    while d < BigInt::from(1 << 16) && d.clone() * d.clone() <= rest {
+        if rest.clone() % d.clone() == BigInt::ZERO {
+            factors.push(d.clone());
+            while rest.clone() % d.clone() == BigInt::ZERO {
+                rest /= d.clone();
+            }
+        }
+        d += 1;
+    }
+
+    let mut composites = Vec::new();
+    if rest > BigInt::from(1) {
+        composites.push(rest);
+    }
+    while let Some(m) = composites.pop() {
+        if is_probable_prime(&m) {
+            factors.push(m);
+        } else {
+            let divisor = pollard_rho(&m);
+            composites.push(m / divisor.clone());
+            composites.push(divisor);
+        }
+    }
+
+    factors.sort();
+    factors.dedup();
+    factors
+}
+
+// Miller-Rabin over the first twelve primes as bases, which is
+// deterministic below 3.3 * 10^24 and overwhelmingly likely above.
+fn is_probable_prime(n: &BigInt) -> bool {
+    let one = BigInt::from(1);
+    if *n < BigInt::from(2) {
+        return false;
+    }
+    let bases = [2u32, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    for b in bases {
+        if *n == BigInt::from(b) {
+            return true;
+        }
+        if n.clone() % BigInt::from(b) == BigInt::ZERO {
+            return false;
+        }
+    }
+
+    let n_minus_one = n.clone() - one.clone();
+    let s = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = n_minus_one.clone() >> s;
+    'witness: for b in bases {
+        let mut x = BigInt::from(b).modpow(&d, n);
+        if x == one || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = x.clone() * x % n;
+            if x == n_minus_one {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+// Returns a non-trivial divisor of the odd composite n, iterating
+// x -> x^2 + c with Floyd cycle detection and retrying with the next c when
+// the cycle closes without one.
+fn pollard_rho(n: &BigInt) -> BigInt {
+    let mut c = BigInt::from(1);
+    loop {
+        let step = |x: &BigInt| (x.clone() * x.clone() + c.clone()) % n;
+        let (mut x, mut y) = (BigInt::from(2), BigInt::from(2));
+        let mut d = BigInt::from(1);
+        while d == BigInt::from(1) {
+            x = step(&x);
+            y = step(&step(&y));
+            let diff = if x > y {
+                x.clone() - y.clone()
+            } else {
+                y.clone() - x.clone()
+            };
+            d = xgcd(diff, n.clone()).2;
+        }
+        if d != *n {
+            return d;
+        }
+        c += 1;
+    }
+}
+
+// Everything derived from the factorization of p - 1, computed on first use
+// and shared by every Field with the same modulus.
+#[derive(Debug)]
+struct FieldTables {
+    multiplicative_generator: BigInt,
+    two_adicity: u64,
+    // roots_of_unity[k] is a primitive 2^k-th root of unity.
+    roots_of_unity: Vec<BigInt>,
+}
+
+impl FieldTables {
+    fn new(p: &BigInt) -> FieldTables {
+        let p_minus_one = p.clone() - BigInt::from(1);
+        let factors = prime_factors(&p_minus_one);
+
+        let mut g = BigInt::from(2);
+        while factors
+            .iter()
+            .any(|q| g.modpow(&(p_minus_one.clone() / q), p) == BigInt::from(1))
+        {
+            g += 1;
+        }
+
+        let two_adicity = p_minus_one.trailing_zeros().unwrap_or(0);
+        let mut roots_of_unity = vec![g.modpow(&(p_minus_one >> two_adicity), p)];
+        for _ in 0..two_adicity {
+            let root = roots_of_unity.last().unwrap();
+            roots_of_unity.push(root.clone() * root.clone() % p);
+        }
+        roots_of_unity.reverse();
+
+        FieldTables {
+            multiplicative_generator: g,
+            two_adicity,
+            roots_of_unity,
+        }
+    }
+}
+
+fn field_tables(p: &BigInt) -> Arc<FieldTables> {
+    static CACHE: OnceLock<Mutex<HashMap<BigInt, Arc<FieldTables>>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(tables) = cache.lock().unwrap().get(p) {
+        return tables.clone();
+    }
+    let tables = Arc::new(FieldTables::new(p));
+    cache.lock().unwrap().insert(p.clone(), tables.clone());
+    tables
+}
+
+// Ordering and hashing follow the canonical value first, then the modulus,
+// consistently with equality.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct FieldElement {
+    #[cfg_attr(feature = "serde", serde(with = "bigint_serde"))]
     pub value: BigInt,
     pub field: Field,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Field {
+    #[cfg_attr(feature = "serde", serde(with = "bigint_serde"))]
     pub p: BigInt,
 }
 
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FieldElement {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "FieldElement")]
+        struct Raw {
+            #[serde(with = "bigint_serde")]
+            value: BigInt,
+            field: Field,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+        if raw.value < BigInt::ZERO || raw.value >= raw.field.p {
+            return Err(serde::de::Error::custom("non-canonical field element"));
+        }
+        Ok(FieldElement::new(raw.value, raw.field))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Field {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Field")]
+        struct Raw {
+            #[serde(with = "bigint_serde")]
+            p: BigInt,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+        if !is_probable_prime(&raw.p) {
+            return Err(serde::de::Error::custom("field modulus is not prime"));
+        }
+        Ok(Field::new(raw.p))
+    }
+}
+
+// Human-readable formats get a decimal string (hex accepted with a 0x
+// prefix), binary formats get the little-endian magnitude bytes.
+#[cfg(feature = "serde")]
+mod bigint_serde {
+    use num_bigint::{BigInt, Sign};
+    use serde::de::{Error, SeqAccess, Visitor};
+    use serde::{Deserializer, Serializer};
+    use std::fmt;
+
+    pub fn serialize<S: Serializer>(value: &BigInt, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&value.to_string())
+        } else {
+            let (sign, bytes) = value.to_bytes_le();
+            if sign == Sign::Minus {
+                return Err(serde::ser::Error::custom("negative value"));
+            }
+            serializer.serialize_bytes(&bytes)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigInt, D::Error> {
+        struct BigIntVisitor;
+
+        impl<'de> Visitor<'de> for BigIntVisitor {
+            type Value = BigInt;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a decimal or 0x-prefixed hex string, or little-endian bytes")
+            }
+
+            fn visit_str<E: Error>(self, v: &str) -> Result<BigInt, E> {
+                super::parse_value(v).map_err(E::custom)
+            }
+
+            fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<BigInt, E> {
+                Ok(BigInt::from_bytes_le(Sign::Plus, v))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<BigInt, A::Error> {
+                let mut bytes = Vec::new();
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    bytes.push(byte);
+                }
+                Ok(BigInt::from_bytes_le(Sign::Plus, &bytes))
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BigIntVisitor)
+        } else {
+            deserializer.deserialize_bytes(BigIntVisitor)
+        }
+    }
+}
+
 impl FieldElement {
     pub fn new(value: BigInt, field: Field) -> FieldElement {
         FieldElement { value, field }
@@ -62,19 +314,44 @@ impl FieldElement {
         self.field.inverse(&mut self.clone())
     }
 
-    pub fn xor(&self, exponent: &mut FieldElement) -> FieldElement {
-        let mut acc = FieldElement::new(BigInt::from(1), Field::new(self.field.p.clone()));
-        let mut val = FieldElement::new(self.value.clone(), self.field.clone());
+    // pow and pow_vartime take time that depends on the exponent, which is
+    // fine for public exponents such as S-box degrees and domain sizes.
+    // pow_ladder runs a Montgomery ladder over a fixed number of bits (the
+    // wider of the exponent and p), so the sequence of field operations does
+    // not depend on the exponent's bits. The BigInt arithmetic underneath
+    // still is not constant time, so secrets belong in CtFieldElement.
+    pub fn pow(&self, exponent: u64) -> FieldElement {
+        self.pow_vartime(&BigUint::from(exponent))
+    }
 
-        let binary_len = format!("{:b}", exponent.value).len();
+    pub fn pow_ladder(&self, exponent: &BigUint) -> FieldElement {
+        let width = exponent.bits().max(self.field.p.bits());
+        self.ladder((0..width).rev().map(|i| exponent.bit(i)))
+    }
 
-        for i in (0..binary_len).rev() {
-            acc = acc.mul(&mut acc.clone());
-            if BigInt::from(2).pow(i as u32) & exponent.value.clone() != BigInt::ZERO {
-                acc = acc.mul(&mut val);
+    pub fn pow_vartime(&self, exponent: &BigUint) -> FieldElement {
+        FieldElement::new(
+            self.value
+                .modpow(&BigInt::from(exponent.clone()), &self.field.p),
+            self.field.clone(),
+        )
+    }
+
+    // Keeps r1 = r0 * self; each bit costs one multiplication and one
+    // squaring whichever way it goes.
+    fn ladder(&self, bits: impl Iterator<Item = bool>) -> FieldElement {
+        let mut r0 = self.field.one();
+        let mut r1 = self.clone();
+        for bit in bits {
+            if bit {
+                r0 = r0.mul(&mut r1);
+                r1 = r1.mul(&mut r1.clone());
+            } else {
+                r1 = r1.mul(&mut r0);
+                r0 = r0.mul(&mut r0.clone());
             }
         }
-        acc
+        r0
     }
 
     pub fn eq(&self, other: &mut FieldElement) -> bool {
@@ -94,11 +371,165 @@ impl FieldElement {
     }
 
     pub fn is_zero(&self) -> bool {
-        if self.value == BigInt::ZERO {
-            true
+        self.value == BigInt::ZERO
+    }
+
+    // Byte encodings are fixed-width: as many bytes as it takes to hold p.
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        let (_, mut bytes) = self.value.to_bytes_le();
+        bytes.resize(self.field.byte_len(), 0);
+        bytes
+    }
+
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    pub fn from_bytes_le(bytes: &[u8], field: Field) -> Result<FieldElement, &'static str> {
+        if bytes.len() != field.byte_len() {
+            return Err("wrong number of bytes for field element");
+        }
+        field.checked_element(BigInt::from_bytes_le(Sign::Plus, bytes))
+    }
+
+    pub fn from_bytes_be(bytes: &[u8], field: Field) -> Result<FieldElement, &'static str> {
+        if bytes.len() != field.byte_len() {
+            return Err("wrong number of bytes for field element");
+        }
+        field.checked_element(BigInt::from_bytes_be(Sign::Plus, bytes))
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.to_bytes_be()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    pub fn from_hex(hex: &str, field: Field) -> Result<FieldElement, &'static str> {
+        let value = parse_digits(hex.strip_prefix("0x").unwrap_or(hex), 16)?;
+        field.checked_element(value)
+    }
+
+    // Euler's criterion: a^((p - 1) / 2) is 1 for non-zero squares and -1
+    // for non-squares.
+    pub fn legendre(&self) -> i8 {
+        let p = &self.field.p;
+        let exponent = (p.clone() - BigInt::from(1)) / BigInt::from(2);
+        let symbol = self.value.modpow(&exponent, p);
+        if symbol == BigInt::ZERO {
+            0
+        } else if symbol == BigInt::from(1) {
+            1
         } else {
-            false
+            -1
+        }
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.legendre() >= 0
+    }
+
+    // Tonelli-Shanks. With p - 1 = q * 2^s the loop runs at most s times,
+    // each time squaring t until it reaches 1.
+    pub fn sqrt(&self) -> Option<(FieldElement, FieldElement)> {
+        match self.legendre() {
+            0 => return Some((self.field.zero(), self.field.zero())),
+            -1 => return None,
+            _ => {}
         }
+        let p = &self.field.p;
+        let (q, s) = self.field.two_adic_decomposition();
+        let z = self.field.quadratic_non_residue();
+
+        let mut m = s;
+        let mut c = z.value.modpow(&q, p);
+        let mut t = self.value.modpow(&q, p);
+        let mut x = self
+            .value
+            .modpow(&((q.clone() + BigInt::from(1)) / BigInt::from(2)), p);
+
+        while t != BigInt::from(1) {
+            let mut i = 0;
+            let mut t_pow = t.clone();
+            while t_pow != BigInt::from(1) {
+                t_pow = t_pow.clone() * t_pow % p;
+                i += 1;
+            }
+            let b = c.modpow(&(BigInt::from(1) << (m - i - 1)), p);
+            x = x * b.clone() % p;
+            c = b.clone() * b % p;
+            t = t * c.clone() % p;
+            m = i;
+        }
+
+        let mut root = FieldElement::new(x, self.field.clone());
+        let negated = root.neg();
+        Some((root, negated))
+    }
+}
+
+impl Default for FieldElement {
+    fn default() -> Self {
+        Field::main().zero()
+    }
+}
+
+impl fmt::Display for FieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.value, f)
+    }
+}
+
+impl fmt::LowerHex for FieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.value, f)
+    }
+}
+
+// Parses a decimal or 0x-prefixed hex string. Only plain digits are
+// accepted: no sign, whitespace or separators, so every value has one
+// spelling per radix apart from leading zeros.
+pub fn parse_value(s: &str) -> Result<BigInt, &'static str> {
+    match s.strip_prefix("0x") {
+        Some(hex) => parse_digits(hex, 16),
+        None => parse_digits(s, 10),
+    }
+}
+
+fn parse_digits(digits: &str, radix: u32) -> Result<BigInt, &'static str> {
+    let error = match radix {
+        16 => "invalid hex string",
+        _ => "invalid decimal string",
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return Err(error);
+    }
+    BigInt::parse_bytes(digits.as_bytes(), radix).ok_or(error)
+}
+
+// Parses a decimal or 0x-prefixed hex string into the main field.
+impl FromStr for FieldElement {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<FieldElement, &'static str> {
+        Field::main().checked_element(parse_value(s)?)
+    }
+}
+
+impl Default for Field {
+    fn default() -> Self {
+        Field::main()
+    }
+}
+
+// Uniform over [0, p), so `rng.sample(&field)` and `field.sample_iter(rng)`
+// work like any other distribution.
+impl Distribution<FieldElement> for Field {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> FieldElement {
+        FieldElement::new(rng.gen_bigint_range(&BigInt::ZERO, &self.p), self.clone())
     }
 }
 
@@ -107,6 +538,39 @@ impl Field {
         Field { p }
     }
 
+    pub fn main() -> Field {
+        Field::new(BigInt::from(1) + BigInt::from(407) * BigInt::from(2).pow(119))
+    }
+
+    // p - 1 = q * 2^s with q odd.
+    pub fn two_adic_decomposition(&self) -> (BigInt, u64) {
+        let p_minus_one = self.p.clone() - BigInt::from(1);
+        let s = p_minus_one.trailing_zeros().unwrap_or(0);
+        (p_minus_one >> s, s)
+    }
+
+    pub fn quadratic_non_residue(&self) -> FieldElement {
+        let mut candidate = BigInt::from(2);
+        loop {
+            let element = FieldElement::new(candidate.clone(), self.clone());
+            if !element.is_square() {
+                return element;
+            }
+            candidate += 1;
+        }
+    }
+
+    pub fn byte_len(&self) -> usize {
+        self.p.bits().div_ceil(8) as usize
+    }
+
+    fn checked_element(&self, value: BigInt) -> Result<FieldElement, &'static str> {
+        if value < BigInt::ZERO || value >= self.p {
+            return Err("non-canonical field element");
+        }
+        Ok(FieldElement::new(value, self.clone()))
+    }
+
     pub fn zero(&self) -> FieldElement {
         FieldElement {
             value: BigInt::ZERO,
@@ -150,8 +614,9 @@ impl Field {
     }
 
     pub fn inverse(&mut self, operand: &mut FieldElement) -> FieldElement {
+        let (a, _, _) = xgcd(operand.value.clone(), self.p.clone());
         FieldElement {
-            value: xgcd(operand.value.clone(), self.p.clone()).0,
+            value: ((a % self.p.clone()) + self.p.clone()) % self.p.clone(),
             field: self.clone(),
         }
     }
@@ -160,54 +625,386 @@ impl Field {
         assert!(!right.is_zero());
         let (a, _, _) = xgcd(right.value.clone(), self.p.clone());
         FieldElement {
-            value: left.value.clone() * a % self.p.clone(),
+            value: ((left.value.clone() * a % self.p.clone()) + self.p.clone()) % self.p.clone(),
             field: self.clone(),
         }
     }
 
-    pub fn generator(&self) -> FieldElement {
-        assert_eq!(
-            self.p.clone(),
-            BigInt::from((1 + 407) * (BigInt::from(2).pow(119)))
-        );
+    // Smallest element of order p - 1.
+    pub fn multiplicative_generator(&self) -> FieldElement {
+        FieldElement::new(
+            field_tables(&self.p).multiplicative_generator.clone(),
+            self.clone(),
+        )
+    }
+
+    pub fn two_adicity(&self) -> u64 {
+        field_tables(&self.p).two_adicity
+    }
 
+    // Entry k is a primitive 2^k-th root of unity, for k = 0..=two_adicity.
+    // Each entry is the square of the next one.
+    pub fn roots_of_unity(&self) -> Vec<FieldElement> {
+        field_tables(&self.p)
+            .roots_of_unity
+            .iter()
+            .map(|root| FieldElement::new(root.clone(), self.clone()))
+            .collect()
+    }
+
+    // Generator of the largest power-of-two subgroup, i.e. a primitive
+    // 2^two_adicity-th root of unity.
+    pub fn generator(&self) -> FieldElement {
+        let tables = field_tables(&self.p);
         FieldElement::new(
-            "85408008396924667383611388730472331217"
-                .parse::<BigInt>()
-                .unwrap(),
+            tables.roots_of_unity[tables.two_adicity as usize].clone(),
             self.clone(),
         )
     }
 
+    // Powers of two come from the cached table; any other n must divide
+    // p - 1 and is served as g^((p - 1) / n).
     pub fn primitive_nth_root(&self, n: BigInt) -> FieldElement {
-        if self.p == BigInt::from((1 + 407) * (BigInt::from(2).pow(119))) {
-            let x: BigInt = BigInt::from(2).pow(119);
-            let r: BigInt = "85408008396924667383611388730472331217"
-                .parse::<BigInt>()
-                .unwrap();
-            assert!(n <= BigInt::from(x.clone()) && (n.clone() & (n.clone() - 1)) == BigInt::ZERO);
-            let mut root = BigInt::from(r);
-            let mut order: BigInt = BigInt::from(x);
-
-            while order != n {
-                root = (root ^ BigInt::from(2)) % BigInt::from(self.p.clone());
-                order = order / 2;
-            }
-            FieldElement {
-                value: root.to_string().parse().unwrap(),
-                field: self.clone(),
-            }
-        } else {
-            assert!(false);
-            FieldElement::new(BigInt::ZERO, self.clone())
+        let tables = field_tables(&self.p);
+        let p_minus_one = self.p.clone() - BigInt::from(1);
+        assert!(n > BigInt::ZERO, "root of unity order must be positive");
+        assert!(
+            p_minus_one.clone() % n.clone() == BigInt::ZERO,
+            "no primitive nth root of unity: n does not divide p - 1"
+        );
+
+        if n.clone() & (n.clone() - BigInt::from(1)) == BigInt::ZERO {
+            let log_n = n.trailing_zeros().unwrap_or(0) as usize;
+            return FieldElement::new(tables.roots_of_unity[log_n].clone(), self.clone());
         }
+        FieldElement::new(
+            tables
+                .multiplicative_generator
+                .modpow(&(p_minus_one / n), &self.p),
+            self.clone(),
+        )
+    }
+
+    pub fn random<R: RngCore + ?Sized>(&self, rng: &mut R) -> FieldElement {
+        Distribution::sample(self, rng)
+    }
+
+    pub fn random_nonzero<R: RngCore + ?Sized>(&self, rng: &mut R) -> FieldElement {
+        FieldElement::new(
+            rng.gen_bigint_range(&BigInt::from(1), &self.p),
+            self.clone(),
+        )
+    }
+
+    // Uniform element of the multiplicative subgroup of the given order,
+    // which must divide p - 1.
+    pub fn random_subgroup_element<R: RngCore + ?Sized>(
+        &self,
+        order: BigInt,
+        rng: &mut R,
+    ) -> FieldElement {
+        let k = rng.gen_bigint_range(&BigInt::ZERO, &order);
+        self.primitive_nth_root(order)
+            .pow_vartime(&k.to_biguint().unwrap())
     }
 
     pub fn sample(&self, ba: &[BigInt]) -> FieldElement {
         let mut acc = BigInt::from(1);
         for b in ba {
-            acc = BigInt::from(acc << 8).modpow(b, &self.p.clone());
+            acc = (acc << 8usize).modpow(b, &self.p.clone());
         }
         FieldElement::new(acc, self.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn goldilocks() -> Field {
+        Field::new(BigInt::from(0xffff_ffff_0000_0001u64))
+    }
+
+    fn assert_sqrt_of_square(x: &FieldElement) {
+        let square = x.clone().mul(&mut x.clone());
+        let (r0, r1) = square.sqrt().expect("a square has a square root");
+        assert!(r0 == *x || r0 == x.clone().neg(), "sqrt({x}^2) = {r0}");
+        assert_eq!(r1, r0.clone().neg());
+        assert_eq!(r0.clone().mul(&mut r0.clone()), square);
+    }
+
+    #[test]
+    fn sqrt_over_small_fields() {
+        // 7, 11 and 19 are 3 mod 4 (s = 1); 13, 17 and 97 have s = 2, 4, 5.
+        for p in [7u64, 11, 13, 17, 19, 97] {
+            let field = Field::new(BigInt::from(p));
+            let mut non_residues = 0;
+            for x in 0..p {
+                let x = FieldElement::new(BigInt::from(x), field.clone());
+                assert_sqrt_of_square(&x);
+                if x.sqrt().is_none() {
+                    assert_eq!(x.legendre(), -1);
+                    assert!(!x.is_square());
+                    non_residues += 1;
+                }
+            }
+            assert_eq!(non_residues, (p - 1) / 2, "p = {p}");
+        }
+    }
+
+    #[test]
+    fn sqrt_over_large_fields() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for field in [Field::main(), goldilocks()] {
+            assert_eq!(field.zero().sqrt(), Some((field.zero(), field.zero())));
+            assert_eq!(field.zero().legendre(), 0);
+            for _ in 0..20 {
+                assert_sqrt_of_square(&field.random(&mut rng));
+            }
+            let non_residue = field.quadratic_non_residue();
+            assert_eq!(non_residue.legendre(), -1);
+            assert_eq!(non_residue.sqrt(), None);
+            let mut x = field.random_nonzero(&mut rng);
+            assert_eq!(
+                x.mul(&mut x.clone()).mul(&mut non_residue.clone()).sqrt(),
+                None
+            );
+        }
+    }
+
+    // Entry k of the table must satisfy w^(2^k) = 1 and, for k > 0,
+    // w^(2^(k-1)) != 1.
+    fn assert_roots_are_primitive(field: &Field, entries: usize) {
+        let roots = field.roots_of_unity();
+        assert_eq!(roots.len(), entries);
+        for (k, root) in roots.iter().enumerate() {
+            let order = BigInt::from(1) << k;
+            assert_eq!(
+                root.value.modpow(&order, &field.p),
+                BigInt::from(1),
+                "entry {k}"
+            );
+            if k > 0 {
+                assert_ne!(
+                    root.value.modpow(&(order >> 1usize), &field.p),
+                    BigInt::from(1),
+                    "entry {k}"
+                );
+            }
+            assert_eq!(*root, field.primitive_nth_root(BigInt::from(1) << k));
+        }
+    }
+
+    #[test]
+    fn main_field_roots_of_unity() {
+        assert_roots_are_primitive(&Field::main(), 120);
+    }
+
+    #[test]
+    fn goldilocks_roots_of_unity() {
+        assert_roots_are_primitive(&goldilocks(), 33);
+    }
+
+    #[test]
+    fn prime_factors_beyond_trial_division() {
+        // Trial division stops at 2^16, so everything but 2 and 11 is left
+        // to Pollard rho, including a repeated factor.
+        let primes = [65537u64, 1_000_003, 4_294_967_311];
+        let n = BigInt::from(8 * 11)
+            * BigInt::from(primes[0]).pow(2)
+            * BigInt::from(primes[1])
+            * BigInt::from(primes[2]);
+        assert_eq!(
+            prime_factors(&n),
+            [2, 11, primes[0], primes[1], primes[2]].map(BigInt::from)
+        );
+        let semiprime = BigInt::from(primes[1]) * BigInt::from(primes[2]);
+        assert_eq!(
+            prime_factors(&semiprime),
+            [primes[1], primes[2]].map(BigInt::from)
+        );
+        assert_eq!(
+            prime_factors(&BigInt::from(primes[2])),
+            [BigInt::from(primes[2])]
+        );
+        assert!(prime_factors(&BigInt::from(1)).is_empty());
+    }
+
+    // w^n = 1 and w^(n/q) != 1 for every prime q dividing n.
+    fn assert_exact_order(field: &Field, n: u64) {
+        let root = field.primitive_nth_root(BigInt::from(n));
+        assert_eq!(root.pow(n), field.one(), "n = {n}");
+        for q in prime_factors(&BigInt::from(n)) {
+            let q = u64::try_from(q).unwrap();
+            assert_ne!(root.pow(n / q), field.one(), "n = {n}, q = {q}");
+        }
+    }
+
+    #[test]
+    fn primitive_roots_of_non_power_of_two_order() {
+        for n in [3, 5, 6, 15, 65537, 3 * 65537 * (1 << 10)] {
+            assert_exact_order(&goldilocks(), n);
+        }
+        for n in [3, 6, 12, 32, 48, 96] {
+            assert_exact_order(&Field::new(BigInt::from(97)), n);
+        }
+        assert_exact_order(&Field::main(), 11 * 37 * (1 << 5));
+    }
+
+    #[test]
+    fn seeded_draws_are_reproducible() {
+        let field = Field::main();
+        let draw = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (
+                field.random(&mut rng),
+                field.random_nonzero(&mut rng),
+                field.random_subgroup_element(BigInt::from(16), &mut rng),
+                field
+                    .clone()
+                    .sample_iter(&mut rng)
+                    .take(3)
+                    .collect::<Vec<_>>(),
+            )
+        };
+        assert_eq!(draw(42), draw(42));
+        assert_ne!(draw(42), draw(43));
+
+        // random is the Distribution impl under another name.
+        let mut rng = StdRng::seed_from_u64(42);
+        assert_eq!(draw(42).0, rng.sample(&field));
+    }
+
+    fn distinct_values(mut draw: impl FnMut() -> FieldElement) -> Vec<BigInt> {
+        let mut seen: Vec<BigInt> = (0..200).map(|_| draw().value).collect();
+        seen.sort();
+        seen.dedup();
+        seen
+    }
+
+    #[test]
+    fn random_draws_cover_a_small_field() {
+        let mut rng = StdRng::seed_from_u64(10);
+        let field = Field::new(BigInt::from(7));
+        assert_eq!(
+            distinct_values(|| field.random(&mut rng)),
+            [0, 1, 2, 3, 4, 5, 6].map(BigInt::from)
+        );
+        assert_eq!(
+            distinct_values(|| field.random_nonzero(&mut rng)),
+            [1, 2, 3, 4, 5, 6].map(BigInt::from)
+        );
+        // The subgroup of order 3 in F_7 is {1, 2, 4}.
+        assert_eq!(
+            distinct_values(|| field.random_subgroup_element(BigInt::from(3), &mut rng)),
+            [1, 2, 4].map(BigInt::from)
+        );
+    }
+
+    #[test]
+    fn random_subgroup_elements_have_dividing_order() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let goldilocks = goldilocks();
+        for order in [1u64, 2, 3, 48, 1 << 32] {
+            for _ in 0..8 {
+                let x = goldilocks.random_subgroup_element(BigInt::from(order), &mut rng);
+                assert_eq!(x.pow(order), goldilocks.one(), "order {order}");
+            }
+        }
+    }
+
+    #[test]
+    fn parse_value_accepts_plain_decimal_and_hex() {
+        assert_eq!(parse_value("255"), Ok(BigInt::from(255)));
+        assert_eq!(parse_value("0xff"), Ok(BigInt::from(255)));
+        assert_eq!(parse_value("0xFF"), Ok(BigInt::from(255)));
+        assert_eq!(parse_value("007"), Ok(BigInt::from(7)));
+        for s in ["", "0x", "-1", "+1", "1_000", " 1", "ff", "0x-1", "0b1"] {
+            assert!(parse_value(s).is_err(), "{s:?}");
+        }
+
+        let field = Field::main();
+        let x = FieldElement::new(BigInt::from(255), field.clone());
+        assert_eq!("0xff".parse::<FieldElement>(), Ok(x.clone()));
+        assert_eq!(FieldElement::from_hex("ff", field.clone()), Ok(x));
+        assert!(FieldElement::from_hex("+ff", field.clone()).is_err());
+        assert!(field.p.to_string().parse::<FieldElement>().is_err());
+    }
+
+    #[test]
+    fn pow_variants_agree() {
+        let mut rng = StdRng::seed_from_u64(9);
+        for field in [Field::main(), goldilocks(), Field::new(BigInt::from(97))] {
+            let p = field.p.to_biguint().unwrap();
+            let bases = [field.zero(), field.one(), field.random(&mut rng)];
+            for base in bases.iter() {
+                for e in [0, 1, 2, 3, 333, rng.next_u64(), u64::MAX] {
+                    let big = BigUint::from(e);
+                    assert_eq!(base.pow(e), base.pow_vartime(&big));
+                    assert_eq!(base.pow(e), base.pow_ladder(&big));
+                }
+                // Exponents at and beyond p.
+                for big in [p.clone() - 1u32, p.clone(), p.clone() * 3u32 + 5u32] {
+                    assert_eq!(base.pow_ladder(&big), base.pow_vartime(&big));
+                }
+            }
+            let x = field.random_nonzero(&mut rng);
+            assert_eq!(x.pow_ladder(&(p.clone() - 1u32)), field.one());
+            assert_eq!(x.pow_vartime(&p), x);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let element = FieldElement::new(BigInt::from(12345), Field::main());
+        let json = serde_json::to_string(&element).unwrap();
+        assert_eq!(
+            serde_json::from_str::<FieldElement>(&json).unwrap(),
+            element
+        );
+        let bytes = bincode::serialize(&element).unwrap();
+        assert_eq!(
+            bincode::deserialize::<FieldElement>(&bytes).unwrap(),
+            element
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_parses_strings_like_from_str() {
+        let p = Field::main().p;
+        for value in ["12345", "0x3039", "+12345", "12_345", "0x"] {
+            let json = format!(r#"{{"value":"{value}","field":{{"p":"{p}"}}}}"#);
+            assert_eq!(
+                serde_json::from_str::<FieldElement>(&json).ok(),
+                value.parse::<FieldElement>().ok(),
+                "{value:?}"
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects_non_canonical_element() {
+        let p = Field::main().p;
+        let json = format!(r#"{{"value":"{p}","field":{{"p":"{p}"}}}}"#);
+        assert!(serde_json::from_str::<FieldElement>(&json).is_err());
+
+        let bytes = bincode::serialize(&FieldElement::new(p.clone(), Field::main())).unwrap();
+        assert!(bincode::deserialize::<FieldElement>(&bytes).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects_invalid_modulus() {
+        for p in ["0", "1", "4", "-7"] {
+            let json = format!(r#"{{"p":"{p}"}}"#);
+            assert!(serde_json::from_str::<Field>(&json).is_err(), "p = {p}");
+        }
+        let json = format!(r#"{{"p":"{}"}}"#, Field::main().p);
+        assert_eq!(serde_json::from_str::<Field>(&json).unwrap(), Field::main());
+    }
+}
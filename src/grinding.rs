@@ -0,0 +1,63 @@
+use sha3::{Digest, Sha3_256};
+
+// Proof of work before the query indices are drawn: the prover searches for
+// a nonce such that SHA3-256(transcript || nonce) starts with `bits` zero
+// bits, which costs about 2^bits hashes and adds `bits` to the query
+// security (StarkParameters::grinding_bits). The nonce is appended as 8
+// little-endian bytes.
+pub fn grind(transcript: &[u8], bits: usize) -> u64 {
+    (0..=u64::MAX)
+        .find(|nonce| verify_grinding(transcript, *nonce, bits))
+        .expect("no nonce reaches the grinding target")
+}
+
+pub fn verify_grinding(transcript: &[u8], nonce: u64, bits: usize) -> bool {
+    let mut hasher = Sha3_256::new();
+    hasher.update(transcript);
+    hasher.update(nonce.to_le_bytes());
+    leading_zero_bits(&hasher.finalize()) >= bits
+}
+
+fn leading_zero_bits(bytes: &[u8]) -> usize {
+    let mut zeros = 0;
+    for byte in bytes {
+        zeros += byte.leading_zeros() as usize;
+        if *byte != 0 {
+            break;
+        }
+    }
+    zeros
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leading_zero_bits_spans_bytes() {
+        assert_eq!(leading_zero_bits(&[0x80, 0]), 0);
+        assert_eq!(leading_zero_bits(&[0x01, 0xff]), 7);
+        assert_eq!(leading_zero_bits(&[0, 0, 0x20]), 18);
+        assert_eq!(leading_zero_bits(&[0, 0]), 16);
+    }
+
+    #[test]
+    fn ground_nonce_verifies() {
+        let transcript = b"merkle roots and fri commitments";
+        for bits in [0, 1, 8, 12] {
+            let nonce = grind(transcript, bits);
+            assert!(verify_grinding(transcript, nonce, bits));
+            // grind returns the first nonce that works.
+            assert!((0..nonce).all(|n| !verify_grinding(transcript, n, bits)));
+        }
+        assert_eq!(grind(transcript, 0), 0);
+    }
+
+    #[test]
+    fn nonce_is_bound_to_the_transcript() {
+        let nonce = grind(b"transcript", 12);
+        assert!(verify_grinding(b"transcript", nonce, 12));
+        assert!(!verify_grinding(b"transcript", nonce, 40));
+        assert!(!verify_grinding(b"another transcript", nonce, 12));
+    }
+}
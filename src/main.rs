@@ -1,24 +1,39 @@
-use num_bigint::{BigInt, RandomBits, ToBigInt};
-use rand::Rng;
+use num_bigint::{BigInt, ToBigInt};
+use rand::distributions::Distribution;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{BTreeSet, HashSet};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
 
+mod constant_time;
+mod domain;
 mod fields;
+mod grinding;
+mod multivariate;
 mod polynomials;
+mod rescue_prime;
+mod rs;
+mod security;
+mod serialization;
+mod sparse;
 
+use crate::constant_time::*;
+use crate::domain::*;
 use crate::fields::*;
+use crate::grinding::*;
 use crate::polynomials::*;
+use crate::rescue_prime::*;
+use crate::security::*;
+use crate::serialization::*;
+use crate::sparse::*;
 
 fn main() {
-    let field = Field::new(BigInt::from(1 + 407) * (BigInt::from(2).pow(119)));
-    let mut element = FieldElement::new(
-        rand::thread_rng().gen_range(BigInt::ZERO..field.p.clone()),
-        Field { p: field.p.clone() },
-    );
-    let mut element2 = FieldElement::new(
-        rand::thread_rng().gen_range(BigInt::ZERO..field.p.clone()),
-        Field { p: field.p.clone() },
-    );
+    let field = Field::main();
+    let mut rng = rand::thread_rng();
+    let mut element = field.random(&mut rng);
+    let mut element2 = field.random(&mut rng);
     let mut poly = Polynomial::new([element.clone(), element2.clone(), element2.clone()].to_vec());
-    let mut poly_zero = Polynomial::new([element.field.zero(), element.field.zero()].to_vec());
+    let poly_zero = Polynomial::new([element.field.zero(), element.field.zero()].to_vec());
     let mut poly_2 =
         Polynomial::new([element.field.zero(), element.clone(), element.field.zero()].to_vec());
 
@@ -31,13 +46,39 @@ fn main() {
     println!("    mul: {}", element.mul(&mut element2).value);
     println!("    sub: {}", element.sub(&mut element2).value);
     println!("    div: {}", element.div(&mut element2).value);
-    println!("    xor: {}", element.xor(&mut element2).value);
+    println!("    pow: {}", element.pow(333).value);
+    let exponent = element2.value.to_biguint().unwrap() + field.p.to_biguint().unwrap();
+    println!("    pow_ladder: {}", element.pow_ladder(&exponent).value);
+    println!("    pow_vartime: {}", element.pow_vartime(&exponent).value);
     println!("    neg: {}", element.neg().value);
     println!("    inv: {}", element.inverse().value);
     println!("    eq: {}", element.eq(&mut element2));
     println!("    neq: {}", element.neq(&mut element2));
     println!("    str: {}", element.str());
     println!("    bytes: {}", element.bytes());
+    println!("    display: {}", element);
+    println!("    hex: {} ({:#x})", element.to_hex(), element);
+    println!(
+        "    hex round trip: {}",
+        FieldElement::from_hex(&element.to_hex(), field.clone()).unwrap() == element
+    );
+    println!(
+        "    bytes round trip: {} {}",
+        FieldElement::from_bytes_le(&element.to_bytes_le(), field.clone()).unwrap() == element,
+        FieldElement::from_bytes_be(&element.to_bytes_be(), field.clone()).unwrap() == element
+    );
+    println!(
+        "    parse: {} {:?}",
+        element.to_string().parse::<FieldElement>().unwrap() == element,
+        field.p.to_string().parse::<FieldElement>()
+    );
+    let square = element.clone().mul(&mut element.clone());
+    println!(
+        "    sqrt: {:?} non-residue: {} legendre: {}",
+        square.sqrt().map(|(r0, _)| r0.value),
+        field.quadratic_non_residue(),
+        field.quadratic_non_residue().legendre()
+    );
     println!("    generator: {}", element.field.generator().value);
     println!(
         "    2nd root of unity: {}",
@@ -47,24 +88,57 @@ fn main() {
         "    16th root of unity: {}",
         element.field.primitive_nth_root(BigInt::from(16)).value
     );
+    let roots = field.roots_of_unity();
+    println!(
+        "    root table: {} entries, 2^119th root: {}",
+        roots.len(),
+        roots[roots.len() - 1]
+    );
+    println!(
+        "    multiplicative generator: {} two-adicity: {}",
+        field.multiplicative_generator(),
+        field.two_adicity()
+    );
+    let goldilocks = Field::new(BigInt::from(0xffff_ffff_0000_0001u64));
+    let cube_root = goldilocks.primitive_nth_root(BigInt::from(3));
+    println!(
+        "    goldilocks: generator {} two-adicity {} cube root {}",
+        goldilocks.multiplicative_generator(),
+        goldilocks.two_adicity(),
+        cube_root
+    );
     println!(
         "    sample: {}",
         element
             .field
             .sample(&[
-                rand::thread_rng().gen_range(BigInt::ZERO..field.p.clone()),
-                rand::thread_rng().gen_range(BigInt::ZERO..field.p.clone()),
-                rand::thread_rng().gen_range(BigInt::ZERO..field.p.clone()),
-                rand::thread_rng().gen_range(BigInt::ZERO..field.p.clone()),
+                field.random(&mut rng).value,
+                field.random(&mut rng).value,
+                field.random(&mut rng).value,
+                field.random(&mut rng).value,
             ])
             .value
     );
+    let mut seeded = StdRng::seed_from_u64(42);
+    let seeded_draw: Vec<FieldElement> = field.clone().sample_iter(&mut seeded).take(3).collect();
+    println!(
+        "    seeded: {} nonzero: {} subgroup of order 16: {}",
+        seeded_draw[0],
+        field.random_nonzero(&mut rng),
+        field.random_subgroup_element(BigInt::from(16), &mut rng)
+    );
+    println!(
+        "    random poly degree: {:?} {:?}",
+        Polynomial::random(5, &field, &mut rng).degree(),
+        Polynomial::random(0, &field, &mut seeded).degree()
+    );
+    println!("    goldilocks sample: {}", rng.sample(&goldilocks));
     println!(
         "    poly coefficients: {}, {}",
         poly.coefficients[0].value, poly.coefficients[1].value
     );
-    println!("    zero poly degree: {}", poly_zero.degree());
-    println!("    poly degree: {}", poly_2.degree());
+    println!("    zero poly degree: {:?}", poly_zero.degree());
+    println!("    poly degree: {:?}", poly_2.degree());
     println!("    poly neg: {}", poly.neg().coefficients[0].value);
     println!(
         "    poly add: {}",
@@ -79,11 +153,27 @@ fn main() {
         poly.mul(&mut poly_2).coefficients[1].value
     );
     println!("    poly neq: {}", poly.neq(&mut poly_2));
-    println!("    poly eq: {}", poly.eq(&mut poly.clone()));
+    println!("    poly eq: {}", poly == poly.clone());
+    let mut padded = poly.clone();
+    padded.coefficients.push(field.zero());
+    println!(
+        "    poly eq ignoring trailing zeros: {} {}",
+        padded == poly,
+        HashSet::from([padded.clone(), poly.clone()]).len()
+    );
+    println!(
+        "    default: {} {}",
+        FieldElement::default(),
+        Polynomial::default().is_zero()
+    );
+    println!(
+        "    deduplicated domain: {}",
+        BTreeSet::from([element.clone(), element2.clone(), element.clone()]).len()
+    );
     println!("    poly is_zero: {}", poly_zero.is_zero());
     println!(
         "    poly leading coefficient: {}",
-        poly.leading_coefficient().value
+        poly.leading_coefficient().unwrap().value
     );
     println!(
         "    poly division: {},{}",
@@ -91,34 +181,97 @@ fn main() {
         poly.divide(&mut poly_2.clone()).unwrap().1.is_zero(),
     );
     println!(
-        "    poly xor: {}",
-        poly.xor(FieldElement {
-            value: 333.to_bigint().unwrap(),
-            field: field.clone()
-        })
-        .coefficients
-        .len(),
+        "    poly truediv: {}",
+        poly.mul(&mut poly_2).truediv(&mut poly_2.clone()) == poly
     );
+    println!(
+        "    poly modulo: {}",
+        poly.modulo(&mut poly_2.clone()).is_zero()
+    );
+    println!(
+        "    poly deep quotient degree: {:?}",
+        poly.deep_quotient(&mut element2.clone()).degree()
+    );
+    println!("    poly pow: {:?}", poly.pow(333).degree());
     println!("    poly eval: {:?}", poly.evaluate(&mut element));
     println!(
         "    poly domain_eval: {:?}",
-        poly.evaluate_domain(&mut [element.clone(), element2.clone()].to_vec())
+        poly.evaluate_domain(&[element.clone(), element2.clone()][..])
     );
+    let points: Vec<FieldElement> = field
+        .clone()
+        .sample_iter(&mut rng)
+        .take(poly.coefficients.len())
+        .collect();
     println!(
-        "    poly interpolation: {:?}",
-        Polynomial::interpolate_domain(
-            &mut poly.coefficients.clone(),
-            &mut poly.evaluate_domain(&mut poly.coefficients.clone())
-        )
-        .coefficients
-        .len()
+        "    poly interpolation: {}",
+        Polynomial::interpolate_domain(&points, &mut poly.evaluate_domain(&points)) == poly
     );
     println!(
         "    poly zerofier: {:?}",
-        Polynomial::zerofier_domain(&mut poly.coefficients.clone())
+        Polynomial::zerofier_domain(&poly.coefficients)
             .coefficients
             .len()
     );
+    let subgroup = EvaluationDomain::new(&field, 8);
+    let coset = EvaluationDomain::coset(&field, 16, field.multiplicative_generator());
+    let mut random_poly = Polynomial::random(7, &field, &mut rng);
+    println!(
+        "    domain: size {} element(3) {} next(7) {} inverse size {}",
+        subgroup.size,
+        subgroup.element(3),
+        subgroup.next(7),
+        subgroup.inverse_size()
+    );
+    let coset_values = coset.evaluate(&random_poly);
+    println!(
+        "    coset evaluation: {} values, interpolated degree {:?}, points {}, zerofier degree {:?}",
+        coset_values.len(),
+        coset.interpolate(&coset_values).degree(),
+        coset.points().len(),
+        Polynomial::zerofier_domain(&coset).degree()
+    );
+    println!(
+        "    vanishing at 1: {}",
+        coset.evaluate_vanishing(&field.one())
+    );
+    let except_last = coset.zerofier_except_last(1);
+    let mut multiple = except_last.to_polynomial().mul(&mut random_poly);
+    println!(
+        "    sparse zerofier: degree {:?} at last element {} quotient degree {:?}",
+        except_last.to_polynomial().degree(),
+        except_last.evaluate(&coset.element(15)),
+        except_last.divide(&mut multiple).unwrap().degree()
+    );
+    println!(
+        "    vanishing divides random polynomial: {}",
+        coset.vanishing().divide(&mut random_poly).is_ok()
+    );
+    let sparse = SparsePolynomial::monomial(1024, field.one())
+        .sub(&SparsePolynomial::monomial(3, element.clone()))
+        .add(&SparsePolynomial::monomial(0, field.one()));
+    let dense = Polynomial::from(&sparse);
+    let mut big_poly = Polynomial::random(1500, &field, &mut rng);
+    println!(
+        "    sparse: terms {} dense degree {:?} square terms {} value {} difference zero: {}",
+        sparse.terms.len(),
+        dense.degree(),
+        sparse.mul(&sparse).terms.len(),
+        sparse.evaluate(&element2),
+        sparse.sub(&sparse).is_zero()
+    );
+    let (quotient, remainder) = big_poly.divide_by_sparse(&sparse).unwrap();
+    println!(
+        "    sparse division: quotient degree {:?} remainder degree {:?}",
+        quotient.degree(),
+        SparsePolynomial::from(&remainder).degree()
+    );
+    println!(
+        "    sub/super domains: sizes {} {} subgroup inside coset: {}",
+        coset.subdomain(2).size,
+        subgroup.superdomain(4).size,
+        subgroup.is_subdomain_of(&coset)
+    );
     println!(
         "    poly scale: {:?}",
         poly.scale(&mut FieldElement::new(
@@ -126,8 +279,52 @@ fn main() {
             field.clone()
         ))
         .leading_coefficient()
+        .unwrap()
         .value
     );
+    let x = Polynomial::new([field.zero(), field.one()].to_vec());
+    println!(
+        "    poly compose with x^2: {:?}",
+        poly.compose(&mut x.shift(1)).degree()
+    );
+    println!(
+        "    poly derivative: {:?}, of product: {:?}",
+        poly.derivative().degree(),
+        poly.mul(&mut poly_2).derivative().degree()
+    );
+    println!(
+        "    poly shift: {:?}, reverse: {:?}",
+        poly.shift(3).degree(),
+        poly.reverse().degree()
+    );
+    let linear = |root: &FieldElement| Polynomial::new([root.clone().neg(), field.one()].to_vec());
+    let mut f = linear(&element).mul(&mut linear(&element2));
+    let mut g = linear(&element).mul(&mut linear(&field.one()));
+    let (s, t, gcd) = f.xgcd(&mut g);
+    println!(
+        "    poly xgcd degrees: s {:?} t {:?} gcd {:?}",
+        s.degree(),
+        t.degree(),
+        gcd.degree()
+    );
+    println!(
+        "    poly gcd(f, f'): {:?} resultant(f, g): {}",
+        f.clone().gcd(&mut f.derivative()).degree(),
+        f.resultant(&mut g)
+    );
+    let mut three = FieldElement::new(BigInt::from(3), field.clone());
+    let mut irreducible = Polynomial::new([three.neg(), field.zero(), field.one()].to_vec());
+    let mut product = f.mul(&mut irreducible);
+    println!(
+        "    poly roots: {} factor degrees: {:?}",
+        product.roots(&mut rng).unwrap().len(),
+        product
+            .factor(&mut rng)
+            .unwrap()
+            .iter()
+            .map(|f| f.degree().unwrap())
+            .collect::<Vec<_>>()
+    );
     println!(
         "    poly colinearity: {}",
         Polynomial::test_colinearity(
@@ -137,5 +334,174 @@ fn main() {
             ]
             .to_vec()
         )
+    );
+
+    println!("Constant time:");
+    for ct_field in [field.clone(), goldilocks.clone()] {
+        let (mut a, mut b) = (ct_field.random(&mut rng), ct_field.random(&mut rng));
+        let ct = CtField::new(&ct_field);
+        let (ct_a, ct_b) = (
+            CtFieldElement::from_field_element(&a, &ct).unwrap(),
+            CtFieldElement::from_field_element(&b, &ct).unwrap(),
+        );
+        let exponent: [u64; 2] = rng.gen();
+        let big_exponent: BigInt = (BigInt::from(exponent[1]) << 64) + BigInt::from(exponent[0]);
+        println!(
+            "    p={} round trip: {} add: {} sub: {} mul: {} neg: {} inverse: {} pow: {}",
+            ct_field.p,
+            ct_a.to_field_element() == a,
+            ct_a.add(&ct_b).to_field_element() == a.add(&mut b),
+            ct_a.sub(&ct_b).to_field_element() == a.sub(&mut b),
+            ct_a.mul(&ct_b).to_field_element() == a.mul(&mut b),
+            ct_a.neg().to_field_element() == a.neg(),
+            ct_a.inverse().to_field_element() == a.inverse(),
+            ct_a.pow(&exponent).to_field_element()
+                == a.pow_vartime(&big_exponent.to_biguint().unwrap())
+        );
+    }
+    let ct_field = CtField::new(&field);
+    let ct_element = CtFieldElement::from_field_element(&element, &ct_field).unwrap();
+    println!(
+        "    select: {} eq: {} {} zero: {}",
+        CtFieldElement::conditional_select(&ct_field.zero(), &ct_element, Choice::from(1))
+            .ct_eq(&ct_element)
+            .unwrap_u8(),
+        ct_element.ct_eq(&ct_element).unwrap_u8(),
+        ct_element.ct_eq(&ct_field.one()).unwrap_u8(),
+        ct_field.zero().inverse().is_zero().unwrap_u8()
+    );
+
+    let rp = RescuePrime::new();
+    println!(
+        "Rescue-Prime: m={} capacity={} N={} security={}",
+        rp.m, rp.capacity, rp.n, rp.security_level
+    );
+    println!("    alpha inverse: {}", rp.alpha_inv);
+    println!("    hash(1): {}", rp.hash(&field.one()).value);
+    println!("    hash(value1): {}", rp.hash(&element).value);
+
+    let trace = rp.trace(&element);
+    let omicron = field.primitive_nth_root(BigInt::from(32));
+    let air = rp.transition_constraints(&omicron);
+    let output = rp.hash(&element);
+    println!(
+        "    trace: {} rows, boundary constraints: {} transition constraints: {}",
+        trace.len(),
+        rp.boundary_constraints(&output).len(),
+        air.len()
+    );
+    let mut first_row = vec![field.one()];
+    first_row.extend(trace[0].iter().cloned());
+    first_row.extend(trace[1].iter().cloned());
+    println!(
+        "    first transition at cycle 0: {}",
+        air[0].evaluate(&mut first_row)
+    );
+
+    println!("Reed-Solomon:");
+    let message: Vec<FieldElement> = field.clone().sample_iter(&mut rng).take(8).collect();
+    let codeword = rs::encode(&message, 4).unwrap();
+    let code_domain = rs::codeword_domain(&field, codeword.len()).unwrap();
+    let mut message_poly = Polynomial::new(message.clone());
+    let ntt_coset = EvaluationDomain::coset(&field, 32, field.multiplicative_generator());
+    println!(
+        "    ntt matches horner: {} {} inverse ntt: {}",
+        codeword == message_poly.evaluate_domain(&code_domain),
+        ntt_coset.evaluate(&message_poly) == message_poly.evaluate_domain(&ntt_coset),
+        ntt_coset.interpolate(&ntt_coset.evaluate(&message_poly)) == message_poly
+    );
+    let mut erased: Vec<Option<FieldElement>> = codeword.iter().cloned().map(Some).collect();
+    for symbol in erased.iter_mut().skip(8) {
+        *symbol = None;
+    }
+    let decoded_erasures = rs::decode_erasures(&erased, 8);
+    erased[0] = None;
+    println!(
+        "    erasures: 24 recovered: {} 25 rejected: {}",
+        decoded_erasures == Ok(message.clone()),
+        rs::decode_erasures(&erased, 8).is_err()
+    );
+    let mut corrupted = codeword.clone();
+    for symbol in corrupted.iter_mut().step_by(2).take(12) {
+        *symbol = symbol.add(&mut field.one());
+    }
+    let decoded = rs::decode(&corrupted, 8);
+    let corrupted_distance = rs::distance(&codeword, &corrupted);
+    corrupted[31] = corrupted[31].add(&mut field.one());
+    println!(
+        "    errors: 12 at distance {} corrected: {} 13 not decoded to message: {}",
+        corrupted_distance,
+        decoded == Ok(message.clone()),
+        rs::decode(&corrupted, 8) != Ok(message.clone())
+    );
+
+    let trace_domain = EvaluationDomain::new(&field, 32);
+    let quotients = rp.transition_quotients(&trace, &trace_domain);
+    let mut tampered = trace.clone();
+    tampered[5][1] = tampered[5][1].add(&mut field.one());
+    println!(
+        "    transition quotient degrees: {:?} tampered trace rejected: {}",
+        quotients
+            .unwrap()
+            .iter()
+            .map(|q| q.degree())
+            .collect::<Vec<_>>(),
+        rp.transition_quotients(&tampered, &trace_domain).is_err()
+    );
+
+    let parameters = StarkParameters::new(
+        field.clone(),
+        4,
+        64,
+        0,
+        3,
+        rp.n + 1,
+        SecurityTarget::Conjectured(100),
     )
+    .unwrap();
+    println!(
+        "Security: expansion={} queries={} grinding={} degree={} trace={}",
+        parameters.expansion_factor,
+        parameters.num_queries,
+        parameters.grinding_bits,
+        parameters.constraint_degree,
+        parameters.trace_length
+    );
+    println!("    {:?}", parameters.security_level());
+    let transcript = format!("{:?}", parameters).into_bytes();
+    let nonce = grind(&transcript, 16);
+    println!(
+        "    grinding nonce for 16 bits: {} verifies: {}",
+        nonce,
+        verify_grinding(&transcript, nonce, 16)
+    );
+    for target in [
+        SecurityTarget::Conjectured(128),
+        SecurityTarget::Proven(100),
+        SecurityTarget::Proven(60),
+    ] {
+        println!(
+            "    {:?}: {:?}",
+            target,
+            StarkParameters::new(field.clone(), 4, 64, 0, 3, rp.n + 1, target)
+                .map(|p| p.security_level())
+        );
+    }
+
+    let mut writer = Writer::new();
+    writer.write_field_element(&element).unwrap();
+    writer.write_polynomial(&poly).unwrap();
+    let encoded = writer.finish();
+    let decode = |bytes: &[u8]| -> Result<(FieldElement, Polynomial), &'static str> {
+        let mut reader = Reader::new(bytes, field.clone())?;
+        let decoded = (reader.read_field_element()?, reader.read_polynomial()?);
+        reader.finish()?;
+        Ok(decoded)
+    };
+    let (decoded_element, decoded_poly) = decode(&encoded).unwrap();
+    println!("Serialization: {} bytes", encoded.len());
+    println!(
+        "    round trip: {}",
+        decoded_element == element && decoded_poly.coefficients == poly.coefficients
+    );
 }
@@ -0,0 +1,157 @@
+use crate::fields::*;
+use crate::polynomials::*;
+use std::collections::HashMap;
+
+// Terms are keyed by exponent vectors, e.g. 17 + 2xy - 19x^6z^12 is
+// {[0, 0, 0]: 17, [1, 1, 0]: 2, [6, 0, 12]: -19}. Keys may have different
+// lengths; missing trailing exponents are zero.
+#[derive(Clone, Debug)]
+pub struct MPolynomial {
+    pub dictionary: HashMap<Vec<usize>, FieldElement>,
+}
+
+impl MPolynomial {
+    pub fn new(dictionary: HashMap<Vec<usize>, FieldElement>) -> MPolynomial {
+        MPolynomial { dictionary }
+    }
+
+    pub fn zero() -> MPolynomial {
+        MPolynomial::new(HashMap::new())
+    }
+
+    pub fn constant(element: FieldElement) -> MPolynomial {
+        MPolynomial::new(HashMap::from([(vec![0], element)]))
+    }
+
+    pub fn variables(num_variables: usize, field: &Field) -> Vec<MPolynomial> {
+        (0..num_variables)
+            .map(|i| {
+                let mut exponents = vec![0; num_variables];
+                exponents[i] = 1;
+                MPolynomial {
+                    dictionary: HashMap::from([(exponents, field.one())]),
+                }
+            })
+            .collect()
+    }
+
+    pub fn lift(polynomial: &Polynomial, variable_index: usize) -> MPolynomial {
+        let mut dictionary = HashMap::new();
+        for (i, coeff) in polynomial.coefficients.iter().enumerate() {
+            if coeff.is_zero() {
+                continue;
+            }
+            let mut exponents = vec![0; variable_index + 1];
+            exponents[variable_index] = i;
+            dictionary.insert(exponents, coeff.clone());
+        }
+        MPolynomial { dictionary }
+    }
+
+    pub fn num_variables(&self) -> usize {
+        self.dictionary.keys().map(|k| k.len()).max().unwrap_or(0)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.dictionary.values().all(|c| c.is_zero())
+    }
+
+    pub fn neg(&mut self) -> MPolynomial {
+        MPolynomial {
+            dictionary: self
+                .dictionary
+                .iter_mut()
+                .map(|(k, v)| (k.clone(), v.neg()))
+                .collect(),
+        }
+    }
+
+    pub fn add(&mut self, other: &mut MPolynomial) -> MPolynomial {
+        let num_variables = self.num_variables().max(other.num_variables());
+        let mut dictionary: HashMap<Vec<usize>, FieldElement> = HashMap::new();
+        for (k, v) in self.dictionary.iter().chain(other.dictionary.iter()) {
+            let key = pad(k, num_variables);
+            let sum = match dictionary.get_mut(&key) {
+                Some(acc) => acc.add(&mut v.clone()),
+                None => v.clone(),
+            };
+            dictionary.insert(key, sum);
+        }
+        dictionary.retain(|_, v| !v.is_zero());
+        MPolynomial { dictionary }
+    }
+
+    pub fn sub(&mut self, other: &mut MPolynomial) -> MPolynomial {
+        self.add(&mut other.neg())
+    }
+
+    pub fn mul(&mut self, other: &mut MPolynomial) -> MPolynomial {
+        let num_variables = self.num_variables().max(other.num_variables());
+        let mut dictionary: HashMap<Vec<usize>, FieldElement> = HashMap::new();
+        for (k0, v0) in self.dictionary.iter_mut() {
+            for (k1, v1) in other.dictionary.iter_mut() {
+                let key: Vec<usize> = pad(k0, num_variables)
+                    .iter()
+                    .zip(pad(k1, num_variables).iter())
+                    .map(|(a, b)| a + b)
+                    .collect();
+                let mut product = v0.mul(v1);
+                let sum = match dictionary.get_mut(&key) {
+                    Some(acc) => acc.add(&mut product),
+                    None => product,
+                };
+                dictionary.insert(key, sum);
+            }
+        }
+        dictionary.retain(|_, v| !v.is_zero());
+        MPolynomial { dictionary }
+    }
+
+    pub fn pow(&self, exponent: u64) -> MPolynomial {
+        if self.is_zero() {
+            return MPolynomial::zero();
+        }
+        let field = self.dictionary.values().next().unwrap().field.clone();
+        let mut acc = MPolynomial::constant(field.one());
+        for i in (0..64 - exponent.leading_zeros()).rev() {
+            acc = acc.mul(&mut acc.clone());
+            if (exponent >> i) & 1 == 1 {
+                acc = acc.mul(&mut self.clone());
+            }
+        }
+        acc
+    }
+
+    pub fn evaluate(&self, point: &mut [FieldElement]) -> FieldElement {
+        let field = point[0].field.clone();
+        let mut acc = field.zero();
+        for (k, v) in self.dictionary.iter() {
+            let mut prod = v.clone();
+            for (i, e) in k.iter().enumerate() {
+                prod = prod.mul(&mut point[i].pow(*e as u64));
+            }
+            acc = acc.add(&mut prod);
+        }
+        acc
+    }
+
+    // Substitutes a univariate polynomial for every variable, e.g. the trace
+    // polynomials into a transition constraint.
+    pub fn evaluate_symbolic(&self, point: &[Polynomial]) -> Polynomial {
+        let mut acc = Polynomial::new(Vec::new());
+        for (k, v) in self.dictionary.iter() {
+            let mut prod = Polynomial::new([v.clone()].to_vec());
+            for (i, e) in k.iter().enumerate() {
+                prod = prod.mul(&mut point[i].pow(*e as u64));
+            }
+            acc = acc.add(&mut prod);
+        }
+        acc
+    }
+}
+
+fn pad(exponents: &[usize], len: usize) -> Vec<usize> {
+    let mut padded = exponents.to_vec();
+    padded.resize(len, 0);
+    padded
+}
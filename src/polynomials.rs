@@ -1,64 +1,200 @@
+use crate::domain::*;
 use crate::fields::*;
+use crate::sparse::*;
 use num_bigint::BigInt;
+use rand::RngCore;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 
-#[derive(Clone, Debug)]
+// Coefficients are stored lowest degree first and never end in a zero, so
+// the zero polynomial is the empty vector and the last coefficient is the
+// leading one. Every constructor and operation below keeps that form.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(into = "SerdePolynomial", try_from = "SerdePolynomial")
+)]
 pub struct Polynomial {
     pub coefficients: Vec<FieldElement>,
 }
 
-impl Polynomial {
-    pub fn new(coef: Vec<FieldElement>) -> Polynomial {
-        Polynomial { coefficients: coef }
-    }
+// The field is written once rather than with every coefficient; the zero
+// polynomial has no coefficients and therefore no field.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "Polynomial")]
+struct SerdePolynomial {
+    field: Option<Field>,
+    #[serde(with = "coefficients_serde")]
+    coefficients: Vec<BigInt>,
+}
 
-    pub fn degree(&self) -> i32 {
-        if self.coefficients.is_empty() {
-            return -1;
+#[cfg(feature = "serde")]
+impl From<Polynomial> for SerdePolynomial {
+    fn from(polynomial: Polynomial) -> SerdePolynomial {
+        let coefficients = polynomial.significant_coefficients();
+        SerdePolynomial {
+            field: coefficients.first().map(|c| c.field.clone()),
+            coefficients: coefficients.iter().map(|c| c.value.clone()).collect(),
         }
-        let zero = self.coefficients[0].field.zero();
-        if self
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SerdePolynomial> for Polynomial {
+    type Error = &'static str;
+
+    fn try_from(raw: SerdePolynomial) -> Result<Polynomial, &'static str> {
+        let Some(field) = raw.field else {
+            return match raw.coefficients.is_empty() {
+                true => Ok(Polynomial::new(Vec::new())),
+                false => Err("polynomial coefficients without a field"),
+            };
+        };
+        let coefficients = raw
             .coefficients
-            .iter()
-            .all(|c| c == &self.coefficients[0].field.zero())
-        {
-            return -1;
+            .into_iter()
+            .map(|value| match value < field.p {
+                true => Ok(FieldElement::new(value, field.clone())),
+                false => Err("non-canonical field element"),
+            })
+            .collect::<Result<Vec<FieldElement>, &'static str>>()?;
+        Ok(Polynomial::new(coefficients))
+    }
+}
+
+// Human-readable formats get a list of strings, each written and parsed like
+// a single field value. Binary formats get a single byte string: one byte
+// giving the width w of the largest value, then every value as w
+// little-endian bytes, so an element costs at most the size of p.
+#[cfg(feature = "serde")]
+mod coefficients_serde {
+    use crate::fields::parse_value;
+    use num_bigint::{BigInt, Sign};
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(values: &[BigInt], serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let strings: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+            return strings.serialize(serializer);
+        }
+        let magnitudes: Vec<Vec<u8>> = values.iter().map(|v| v.to_bytes_le().1).collect();
+        let width = magnitudes.iter().map(|m| m.len()).max().unwrap_or(0);
+        if width > u8::MAX as usize {
+            return Err(serde::ser::Error::custom("coefficient too large"));
         }
-        let mut maxindex: i32 = 0;
-        for (i, coef) in self.coefficients.iter().enumerate() {
-            if coef != &zero {
-                maxindex = i as i32;
+        let mut bytes = vec![width as u8];
+        for mut magnitude in magnitudes {
+            magnitude.resize(width, 0);
+            bytes.extend(magnitude);
+        }
+        serializer.serialize_bytes(&bytes)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<BigInt>, D::Error> {
+        if deserializer.is_human_readable() {
+            return Vec::<String>::deserialize(deserializer)?
+                .iter()
+                .map(|v| parse_value(v).map_err(D::Error::custom))
+                .collect();
+        }
+        let bytes = byte_string(deserializer)?;
+        let Some((&width, values)) = bytes.split_first() else {
+            return Err(D::Error::custom("missing coefficient width"));
+        };
+        if width == 0 {
+            return match values.is_empty() {
+                true => Ok(Vec::new()),
+                false => Err(D::Error::custom("coefficients of width zero")),
+            };
+        }
+        if values.len() % width as usize != 0 {
+            return Err(D::Error::custom("coefficient bytes do not match the width"));
+        }
+        Ok(values
+            .chunks(width as usize)
+            .map(|chunk| BigInt::from_bytes_le(Sign::Plus, chunk))
+            .collect())
+    }
+
+    fn byte_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for BytesVisitor {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a byte string")
+            }
+
+            fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+                Ok(v.to_vec())
+            }
+
+            fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+                Ok(v)
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Vec<u8>, A::Error> {
+                let mut bytes = Vec::new();
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    bytes.push(byte);
+                }
+                Ok(bytes)
             }
         }
-        maxindex
+
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+impl Polynomial {
+    pub fn new(mut coef: Vec<FieldElement>) -> Polynomial {
+        while coef.last().is_some_and(|c| c.is_zero()) {
+            coef.pop();
+        }
+        Polynomial { coefficients: coef }
+    }
+
+    // Uniform among polynomials of exactly the given degree: the leading
+    // coefficient is drawn from the non-zero elements.
+    pub fn random<R: RngCore + ?Sized>(degree: usize, field: &Field, rng: &mut R) -> Polynomial {
+        let mut coefficients: Vec<FieldElement> = (0..degree).map(|_| field.random(rng)).collect();
+        coefficients.push(field.random_nonzero(rng));
+        Polynomial::new(coefficients)
+    }
+
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.iter().rposition(|c| !c.is_zero())
     }
 
     pub fn neg(&mut self) -> Polynomial {
-        let mut negs = self.coefficients.clone();
-        let negs = negs
-            .iter_mut()
-            .map(|c| c.neg())
-            .collect::<Vec<FieldElement>>();
-        Polynomial { coefficients: negs }
+        Polynomial::new(self.coefficients.iter_mut().map(|c| c.neg()).collect())
     }
 
     pub fn add(&mut self, other: &mut Polynomial) -> Polynomial {
-        if self.degree() == -1 {
-            return other.clone();
-        } else if other.degree() == -1 {
-            return self.clone();
+        if self.is_zero() {
+            return Polynomial::new(other.coefficients.clone());
+        } else if other.is_zero() {
+            return Polynomial::new(self.coefficients.clone());
         }
         let field = self.coefficients[0].field.clone();
-        let degree = self.degree().max(other.degree()) + 1;
-        let mut coeffs: Vec<FieldElement> = vec![field.zero(); degree.try_into().unwrap()];
+        let len = self.coefficients.len().max(other.coefficients.len());
+        let mut coeffs: Vec<FieldElement> = vec![field.zero(); len];
         for (i, coeff) in self.coefficients.iter_mut().enumerate() {
             coeffs[i] = coeffs[i].add(coeff);
         }
         for (i, coeff) in other.coefficients.iter_mut().enumerate() {
             coeffs[i] = coeffs[i].add(coeff);
         }
-        Polynomial {
-            coefficients: coeffs,
-        }
+        Polynomial::new(coeffs)
     }
 
     pub fn sub(&mut self, other: &mut Polynomial) -> Polynomial {
@@ -66,224 +202,926 @@ impl Polynomial {
     }
 
     pub fn mul(&mut self, other: &mut Polynomial) -> Polynomial {
-        if self.coefficients == [] || other.coefficients == [] {
-            return Polynomial {
-                coefficients: Vec::new(),
-            };
-        }
-        let zero = self.coefficients[0].field.zero();
+        let (Some(self_degree), Some(other_degree)) = (self.degree(), other.degree()) else {
+            return Polynomial::new(Vec::new());
+        };
         let field = self.coefficients[0].field.clone();
-        let degree = self.degree().checked_add(other.degree()).unwrap();
-        let mut buf: Vec<FieldElement> = vec![field.zero(); degree as usize + 2];
-        for (i, s_coeff) in self.coefficients.iter_mut().enumerate() {
+        let mut buf: Vec<FieldElement> = vec![field.zero(); self_degree + other_degree + 1];
+        for (i, s_coeff) in self
+            .coefficients
+            .iter_mut()
+            .take(self_degree + 1)
+            .enumerate()
+        {
             if s_coeff.is_zero() {
                 continue;
             }
-            for (j, o_coeff) in other.coefficients.iter_mut().enumerate() {
+            for (j, o_coeff) in other
+                .coefficients
+                .iter_mut()
+                .take(other_degree + 1)
+                .enumerate()
+            {
                 buf[i + j] = buf[i + j].add(&mut s_coeff.mul(o_coeff));
             }
         }
-        Polynomial { coefficients: buf }
-    }
-    pub fn eq(&mut self, other: &mut Polynomial) -> bool {
-        if self.degree() != other.degree() {
-            return false;
-        }
-        if self.degree() == -1 {
-            return true;
-        }
-        self.coefficients.eq(&other.coefficients)
+        Polynomial::new(buf)
     }
     pub fn neq(&mut self, other: &mut Polynomial) -> bool {
-        !self.eq(other)
+        *self != *other
+    }
+    // The coefficients up to the degree, in case the public field was
+    // extended by hand.
+    pub fn significant_coefficients(&self) -> &[FieldElement] {
+        &self.coefficients[..self.degree().map_or(0, |d| d + 1)]
     }
     pub fn is_zero(&self) -> bool {
-        if self.degree() == -1 {
-            return true;
-        }
-        false
+        self.degree().is_none()
     }
-    pub fn leading_coefficient(&self) -> FieldElement {
-        self.coefficients[self.degree() as usize].clone()
+    pub fn leading_coefficient(&self) -> Option<FieldElement> {
+        self.degree().map(|d| self.coefficients[d].clone())
     }
     pub fn divide(
         &mut self,
         denominator: &mut Polynomial,
     ) -> Result<(Polynomial, Polynomial), &'static str> {
-        if denominator.degree() == -1 {
-            println!("w");
+        let Some(denominator_degree) = denominator.degree() else {
             return Err("dividing with zero polynomial");
-        }
-        if self.degree() < denominator.degree() {
-            return Ok((
-                Polynomial {
-                    coefficients: [].to_vec(),
-                },
-                self.clone(),
-            ));
+        };
+        let self_degree = match self.degree() {
+            Some(d) if d >= denominator_degree => d,
+            _ => {
+                return Ok((
+                    Polynomial::new(Vec::new()),
+                    Polynomial::new(self.coefficients.clone()),
+                ))
+            }
+        };
+        if denominator_degree == 1 {
+            return Ok(self.divide_linear(denominator));
         }
         let field = denominator.coefficients[0].field.clone();
-        let mut remainder = Polynomial {
-            coefficients: self.coefficients.clone(),
-        };
-        let mut quotient_coefficients =
-            vec![field.zero().clone(); (self.degree() - denominator.degree() + 1) as usize];
-        for (i, coeff) in quotient_coefficients.clone().iter_mut().enumerate() {
-            if remainder.degree() < denominator.degree() {
+        let mut denominator_leading = denominator.leading_coefficient().unwrap();
+        let mut remainder = Polynomial::new(self.coefficients.clone());
+        let mut quotient_coefficients = vec![field.zero(); self_degree - denominator_degree + 1];
+        while let Some(remainder_degree) = remainder.degree() {
+            if remainder_degree < denominator_degree {
                 break;
             }
             let coefficient = remainder
                 .leading_coefficient()
-                .div(&mut denominator.leading_coefficient());
-            let shift = remainder.degree() - denominator.degree();
-            let mut subtractee = Polynomial {
-                coefficients: vec![field.zero().clone(); shift as usize],
-            };
-            subtractee.coefficients.push(coefficient.clone());
-            subtractee.mul(denominator);
-            quotient_coefficients[shift as usize] = coefficient.clone();
+                .unwrap()
+                .div(&mut denominator_leading);
+            let shift = remainder_degree - denominator_degree;
+            let mut subtractee = vec![field.zero(); shift];
+            subtractee.push(coefficient.clone());
+            let mut subtractee = Polynomial::new(subtractee).mul(denominator);
+            quotient_coefficients[shift] = coefficient;
             remainder = remainder.sub(&mut subtractee);
         }
-        let quotient = Polynomial {
-            coefficients: quotient_coefficients,
+        Ok((Polynomial::new(quotient_coefficients), remainder))
+    }
+    // Synthetic division by a*x + b: divide by the monic x - root with
+    // root = -b/a, then scale the quotient by 1/a. The remainder is f(root).
+    fn divide_linear(&mut self, denominator: &mut Polynomial) -> (Polynomial, Polynomial) {
+        let mut a = denominator.coefficients[1].clone();
+        let mut root = denominator.coefficients[0].clone().neg().div(&mut a);
+        let mut a_inv = a.inverse();
+
+        let n = self.degree().unwrap();
+        let mut quotient_coefficients = vec![root.field.zero(); n];
+        let mut carry = root.field.zero();
+        for i in (1..=n).rev() {
+            carry = carry.mul(&mut root).add(&mut self.coefficients[i]);
+            quotient_coefficients[i - 1] = carry.clone();
+        }
+        let remainder = carry.mul(&mut root).add(&mut self.coefficients[0]);
+
+        for coeff in quotient_coefficients.iter_mut() {
+            *coeff = coeff.mul(&mut a_inv);
+        }
+        (
+            Polynomial::new(quotient_coefficients),
+            Polynomial::new([remainder].to_vec()),
+        )
+    }
+
+    // Long division by a divisor with t terms costs O(t) per quotient
+    // coefficient instead of O(deg divisor), and the divisor is never
+    // expanded into a dense vector.
+    pub fn divide_by_sparse(
+        &mut self,
+        denominator: &SparsePolynomial,
+    ) -> Result<(Polynomial, Polynomial), &'static str> {
+        let (Some(denominator_degree), Some(mut denominator_leading)) =
+            (denominator.degree(), denominator.leading_coefficient())
+        else {
+            return Err("dividing with zero polynomial");
         };
-        Ok((quotient, remainder))
+        let mut remainder = self.coefficients.clone();
+        if remainder.len() <= denominator_degree {
+            return Ok((Polynomial::new(Vec::new()), Polynomial::new(remainder)));
+        }
+
+        let field = denominator_leading.field.clone();
+        let mut leading_inverse = denominator_leading.inverse();
+        let mut quotient = vec![field.zero(); remainder.len() - denominator_degree];
+        for i in (denominator_degree..remainder.len()).rev() {
+            let mut coefficient = std::mem::replace(&mut remainder[i], field.zero());
+            coefficient = coefficient.mul(&mut leading_inverse);
+            for (d, c) in denominator.terms.range(..denominator_degree) {
+                let index = i - denominator_degree + d;
+                remainder[index] = remainder[index].sub(&mut c.clone().mul(&mut coefficient));
+            }
+            quotient[i - denominator_degree] = coefficient;
+        }
+        Ok((Polynomial::new(quotient), Polynomial::new(remainder)))
+    }
+
+    // (f(x) - f(z)) / (x - z), which is a polynomial for every z.
+    pub fn deep_quotient(&mut self, point: &mut FieldElement) -> Polynomial {
+        let field = point.field.clone();
+        let mut denominator = Polynomial::new([point.neg(), field.one()].to_vec());
+        if self.degree().unwrap_or(0) < 1 {
+            return Polynomial::new(Vec::new());
+        }
+        self.divide_linear(&mut denominator).0
     }
+
     pub fn truediv(&mut self, other: &mut Polynomial) -> Polynomial {
         let (quo, rem) = self.divide(other).unwrap();
         assert!(rem.is_zero());
         quo
     }
     pub fn modulo(&mut self, other: &mut Polynomial) -> Polynomial {
-        let (quo, rem) = self.divide(other).unwrap();
+        let (_, rem) = self.divide(other).unwrap();
         rem
     }
-    pub fn xor(&mut self, exponent: FieldElement) -> Polynomial {
+    pub fn pow(&self, exponent: u64) -> Polynomial {
         if self.is_zero() {
-            return Polynomial {
-                coefficients: Vec::new(),
-            };
-        }
-        if exponent.value == BigInt::ZERO {
-            return Polynomial {
-                coefficients: [self.coefficients[0].field.one()].to_vec(),
-            };
+            return Polynomial::new(Vec::new());
         }
-        let mut acc = Polynomial {
-            coefficients: [self.coefficients[0].field.one()].to_vec(),
-        };
-
-        let bytes = exponent.value.to_signed_bytes_be();
-
-        for byte in bytes {
-            for i in (0..8).rev() {
-                acc = acc.mul(&mut acc.clone());
-                if (byte >> i) & 1 == 1 {
-                    acc = acc.mul(self);
-                }
+        let mut acc = Polynomial::new([self.coefficients[0].field.one()].to_vec());
+        for i in (0..64 - exponent.leading_zeros()).rev() {
+            acc = acc.mul(&mut acc.clone());
+            if (exponent >> i) & 1 == 1 {
+                acc = acc.mul(&mut self.clone());
             }
         }
         acc
     }
 
     pub fn evaluate(&mut self, point: &mut FieldElement) -> FieldElement {
-        let mut xi = point.field.one();
         let mut value = point.field.zero();
-
-        for mut c in self.coefficients.clone() {
-            value = value.clone().add(&mut c.mul(&mut xi));
-            xi = xi.clone().mul(point);
+        for c in self.coefficients.iter_mut().rev() {
+            value = value.mul(point).add(c);
         }
         value
     }
 
-    pub fn evaluate_domain(&mut self, domain: &mut Vec<FieldElement>) -> Vec<FieldElement> {
+    pub fn evaluate_domain<D: Domain + ?Sized>(&mut self, domain: &D) -> Vec<FieldElement> {
         domain
+            .points()
             .iter_mut()
-            .map(|mut p| self.evaluate(&mut p))
+            .map(|p| self.evaluate(p))
             .collect()
     }
 
-    pub fn interpolate_domain(
-        domain: &mut Vec<FieldElement>,
-        values: &mut Vec<FieldElement>,
+    // Lagrange interpolation through the domain's zerofier Z: the basis
+    // polynomial for x_i is Z / (x - x_i), scaled by its inverse value at x_i.
+    pub fn interpolate_domain<D: Domain + ?Sized>(
+        domain: &D,
+        values: &mut [FieldElement],
     ) -> Polynomial {
-        assert!(domain.len() == values.len());
-        assert!(domain.len() > 0);
+        let mut points = domain.points();
+        assert!(points.len() == values.len());
+        assert!(!points.is_empty());
 
-        let field = domain[0].field.clone();
-        let mut x = Polynomial {
-            coefficients: [field.zero(), field.one()].to_vec(),
-        };
-        let mut acc = Polynomial {
-            coefficients: Vec::new(),
+        let mut zerofier = domain.zerofier();
+        let mut acc = Polynomial::new(Vec::new());
+        for (point, value) in points.iter_mut().zip(values.iter_mut()) {
+            let mut basis = zerofier.deep_quotient(point);
+            let mut weight = value.div(&mut basis.evaluate(point));
+            acc = acc.add(&mut basis.scale_coefficients(&mut weight));
+        }
+        acc
+    }
+
+    pub fn zerofier_domain<D: Domain + ?Sized>(domain: &D) -> Polynomial {
+        domain.zerofier()
+    }
+
+    // f(factor * x): the i-th coefficient picks up factor^i.
+    pub fn scale(&mut self, factor: &mut FieldElement) -> Polynomial {
+        let mut power = factor.field.one();
+        let mut scaled: Vec<FieldElement> = Vec::new();
+        for coeff in self.coefficients.iter_mut() {
+            scaled.push(coeff.mul(&mut power));
+            power = power.mul(factor);
+        }
+        Polynomial::new(scaled)
+    }
+
+    // f(g(x)), by Horner's rule over the coefficients of f.
+    pub fn compose(&mut self, other: &mut Polynomial) -> Polynomial {
+        let mut acc = Polynomial::new(Vec::new());
+        for coeff in self.coefficients.iter().rev() {
+            acc = acc
+                .mul(other)
+                .add(&mut Polynomial::new([coeff.clone()].to_vec()));
+        }
+        acc
+    }
+
+    pub fn derivative(&mut self) -> Polynomial {
+        let derived = self
+            .coefficients
+            .iter_mut()
+            .enumerate()
+            .skip(1)
+            .map(|(i, coeff)| {
+                coeff.mul(&mut FieldElement::new(BigInt::from(i), coeff.field.clone()))
+            })
+            .collect();
+        Polynomial::new(derived)
+    }
+
+    // x^k * f(x).
+    pub fn shift(&self, k: usize) -> Polynomial {
+        if self.is_zero() {
+            return Polynomial::new(Vec::new());
+        }
+        let mut shifted = vec![self.coefficients[0].field.zero(); k];
+        shifted.extend(self.coefficients.iter().cloned());
+        Polynomial::new(shifted)
+    }
+
+    // x^deg(f) * f(1/x), i.e. the coefficients in reverse order.
+    pub fn reverse(&self) -> Polynomial {
+        let mut reversed = self.significant_coefficients().to_vec();
+        reversed.reverse();
+        Polynomial::new(reversed)
+    }
+
+    fn scale_coefficients(&mut self, factor: &mut FieldElement) -> Polynomial {
+        Polynomial::new(
+            self.coefficients
+                .iter_mut()
+                .map(|c| c.mul(factor))
+                .collect(),
+        )
+    }
+
+    // Monic greatest common divisor; zero only if both inputs are zero.
+    pub fn gcd(&mut self, other: &mut Polynomial) -> Polynomial {
+        let (_, _, g) = self.xgcd(other);
+        g
+    }
+
+    // The polynomial analog of fields::xgcd: returns (s, t, g) with
+    // s * self + t * other = g and g the monic gcd.
+    pub fn xgcd(&mut self, other: &mut Polynomial) -> (Polynomial, Polynomial, Polynomial) {
+        let Some(field) = self
+            .coefficients
+            .first()
+            .or(other.coefficients.first())
+            .map(|c| c.field.clone())
+        else {
+            let zero = Polynomial::new(Vec::new());
+            return (zero.clone(), zero.clone(), zero);
         };
-        println!("{}", domain.len());
-        for i in 0..domain.len() {
-            println!("i{}", i);
-            let mut prod = Polynomial {
-                coefficients: [values[i].clone()].to_vec(),
+        let (mut old_r, mut r) = (self.clone(), other.clone());
+        let (mut old_s, mut s) = (
+            Polynomial::new([field.one()].to_vec()),
+            Polynomial::new(Vec::new()),
+        );
+        let (mut old_t, mut t) = (
+            Polynomial::new(Vec::new()),
+            Polynomial::new([field.one()].to_vec()),
+        );
+
+        while !r.is_zero() {
+            let (mut quotient, _) = old_r.divide(&mut r).unwrap();
+            update_step(&mut r, &mut old_r, &mut quotient);
+            update_step(&mut s, &mut old_s, &mut quotient);
+            update_step(&mut t, &mut old_t, &mut quotient);
+        }
+
+        match old_r.leading_coefficient() {
+            Some(leading) => {
+                let mut inv = leading.clone().inverse();
+                (
+                    old_s.scale_coefficients(&mut inv),
+                    old_t.scale_coefficients(&mut inv),
+                    old_r.scale_coefficients(&mut inv),
+                )
+            }
+            None => (old_s, old_t, old_r),
+        }
+    }
+
+    // Res(f, g) via the Euclidean algorithm:
+    // Res(f, g) = (-1)^(deg f * deg g) * lc(g)^(deg f - deg r) * Res(g, r)
+    // with r = f mod g, and Res(f, c) = c^(deg f) for a constant c.
+    pub fn resultant(&mut self, other: &mut Polynomial) -> FieldElement {
+        let field = self
+            .coefficients
+            .first()
+            .or(other.coefficients.first())
+            .map(|c| c.field.clone())
+            .expect("resultant of two zero polynomials");
+        let power = |base: &FieldElement, exponent: usize| base.pow(exponent as u64);
+
+        let (mut a, mut b) = (self.clone(), other.clone());
+        let mut acc = field.one();
+        loop {
+            let (Some(deg_a), Some(deg_b)) = (a.degree(), b.degree()) else {
+                return field.zero();
             };
-            for j in 0..domain.len() {
-                println!("j{}", j);
-                if j == i {
-                    continue;
-                }
-                prod = prod
-                    .mul(&mut x.sub(&mut Polynomial {
-                        coefficients: [domain[j].clone()].to_vec(),
-                    }))
-                    .mul(&mut Polynomial {
-                        coefficients: [domain[i].clone().sub(&mut domain[j].clone()).inverse()]
-                            .to_vec(),
-                    })
+            if deg_b == 0 {
+                return acc.mul(&mut power(&b.coefficients[0], deg_a));
+            }
+            if deg_a == 0 {
+                return acc.mul(&mut power(&a.coefficients[0], deg_b));
             }
-            acc = acc.add(&mut prod);
+            let r = a.modulo(&mut b);
+            let Some(deg_r) = r.degree() else {
+                return field.zero();
+            };
+            if deg_a * deg_b % 2 == 1 {
+                acc = acc.neg();
+            }
+            acc = acc.mul(&mut power(&b.leading_coefficient().unwrap(), deg_a - deg_r));
+            (a, b) = (b, r);
         }
-        acc
     }
 
-    pub fn zerofier_domain(domain: &mut Vec<FieldElement>) -> Polynomial {
-        let field = domain[0].field.clone();
-        let mut x = Polynomial {
-            coefficients: [field.zero(), field.one()].to_vec(),
-        };
-        let mut acc = Polynomial {
-            coefficients: [field.one()].to_vec(),
-        };
-        for d in domain {
-            acc = acc.mul(&mut x.sub(&mut Polynomial {
-                coefficients: [d.clone()].to_vec(),
-            }));
+    fn pow_mod(&mut self, exponent: &BigInt, modulus: &mut Polynomial) -> Polynomial {
+        let field = modulus.coefficients[0].field.clone();
+        let mut base = self.modulo(modulus);
+        let mut acc = Polynomial::new([field.one()].to_vec()).modulo(modulus);
+        for i in (0..exponent.bits()).rev() {
+            acc = acc.mul(&mut acc.clone()).modulo(modulus);
+            if exponent.bit(i) {
+                acc = acc.mul(&mut base).modulo(modulus);
+            }
         }
         acc
     }
 
-    pub fn scale(&mut self, factor: &mut FieldElement) -> Polynomial {
+    // Distinct roots, in ascending order. The roots are exactly the linear
+    // factors of gcd(f, x^p - x), which are then split apart with random
+    // polynomials drawn from rng. p must be odd.
+    pub fn roots<R: RngCore + ?Sized>(
+        &mut self,
+        rng: &mut R,
+    ) -> Result<Vec<FieldElement>, &'static str> {
+        if self.is_zero() {
+            return Err("every element is a root of the zero polynomial");
+        }
         let field = self.coefficients[0].field.clone();
-        let mut scaled: Vec<FieldElement> = Vec::new();
-        for i in 0..self.coefficients.len() {
-            let value = factor
-                .xor(&mut FieldElement::new(i.into(), field.clone()))
-                .mul(&mut self.coefficients[i]);
+        let mut x = Polynomial::new([field.zero(), field.one()].to_vec());
+        let mut x_p = x.pow_mod(&field.p, self);
+        let mut linear_part = self.gcd(&mut x_p.sub(&mut x));
+
+        let mut roots: Vec<FieldElement> = linear_part
+            .equal_degree_split(1, rng)
+            .iter_mut()
+            .map(|factor| factor.coefficients[0].neg())
+            .collect();
+        roots.sort();
+        Ok(roots)
+    }
+
+    // Monic irreducible factors of a square-free polynomial: distinct-degree
+    // factorization, then Cantor-Zassenhaus on each equal-degree part. p must
+    // be odd.
+    pub fn factor<R: RngCore + ?Sized>(
+        &mut self,
+        rng: &mut R,
+    ) -> Result<Vec<Polynomial>, &'static str> {
+        if self.is_zero() {
+            return Err("cannot factor the zero polynomial");
+        }
+        if self.gcd(&mut self.clone().derivative()).degree() != Some(0) {
+            return Err("factor() expects a square-free polynomial");
+        }
+        let field = self.coefficients[0].field.clone();
+        let mut x = Polynomial::new([field.zero(), field.one()].to_vec());
 
-            scaled.push(value);
+        let mut factors = Vec::new();
+        let mut rest = self.clone();
+        let mut h = x.clone();
+        let mut d = 1;
+        while rest.degree().unwrap() >= 2 * d {
+            h = h.pow_mod(&field.p, &mut rest);
+            let mut part = rest.gcd(&mut h.sub(&mut x));
+            if part.degree() != Some(0) {
+                factors.extend(part.equal_degree_split(d, rng));
+                rest = rest.truediv(&mut part);
+                h = h.modulo(&mut rest);
+            }
+            d += 1;
+        }
+        if rest.degree() != Some(0) {
+            let mut inv = rest.leading_coefficient().unwrap().inverse();
+            factors.push(rest.scale_coefficients(&mut inv));
         }
-        Polynomial {
-            coefficients: scaled,
+
+        factors.sort();
+        Ok(factors)
+    }
+
+    // Splits a monic product of distinct irreducible factors of degree d:
+    // for random a, gcd(f, a^((p^d - 1) / 2) - 1) is a nontrivial factor
+    // about half of the time.
+    fn equal_degree_split<R: RngCore + ?Sized>(
+        &mut self,
+        d: usize,
+        rng: &mut R,
+    ) -> Vec<Polynomial> {
+        let n = match self.degree() {
+            None | Some(0) => return Vec::new(),
+            Some(n) if n == d => return [self.clone()].to_vec(),
+            Some(n) => n,
+        };
+        let field = self.coefficients[0].field.clone();
+        let exponent = (field.p.pow(d as u32) - BigInt::from(1)) / BigInt::from(2);
+        let mut one = Polynomial::new([field.one()].to_vec());
+
+        loop {
+            let mut a = Polynomial::new((0..n).map(|_| field.random(rng)).collect());
+            if a.degree().unwrap_or(0) == 0 {
+                continue;
+            }
+            let mut power = a.pow_mod(&exponent, self);
+            let mut candidate = self.gcd(&mut power.sub(&mut one));
+            if let Some(1..) = candidate.degree().filter(|&k| k < n) {
+                let mut cofactor = self.truediv(&mut candidate);
+                let mut factors = candidate.equal_degree_split(d, rng);
+                factors.extend(cofactor.equal_degree_split(d, rng));
+                return factors;
+            }
         }
     }
 
-    pub fn test_colinearity(points: &mut Vec<(FieldElement, FieldElement)>) -> bool {
-        let mut domain: Vec<FieldElement> = points.iter().map(|point| point.0.clone()).collect();
+    pub fn test_colinearity(points: &mut [(FieldElement, FieldElement)]) -> bool {
+        let domain: Vec<FieldElement> = points.iter().map(|point| point.0.clone()).collect();
         let mut values: Vec<FieldElement> = points.iter().map(|point| point.1.clone()).collect();
-        println!("{:?}", domain);
-        println!("{:?}", domain.len());
-        println!("{:?}", values);
-        println!("{:?}", values.len());
-
-        let poly = Polynomial::interpolate_domain(&mut domain, &mut values);
-        println!("{:?}", poly);
-        poly.degree() <= 1
+
+        let poly = Polynomial::interpolate_domain(&domain, &mut values);
+        poly.degree().is_none_or(|d| d <= 1)
+    }
+}
+
+fn update_step(a: &mut Polynomial, old_a: &mut Polynomial, q: &mut Polynomial) {
+    let temp = a.clone();
+    *a = old_a.sub(&mut q.mul(a));
+    *old_a = temp;
+}
+
+impl PartialEq for Polynomial {
+    fn eq(&self, other: &Polynomial) -> bool {
+        self.significant_coefficients() == other.significant_coefficients()
+    }
+}
+
+impl Eq for Polynomial {}
+
+impl Hash for Polynomial {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.significant_coefficients().hash(state);
+    }
+}
+
+// By degree, then by the significant coefficients from the constant term
+// up, so that trailing zeros are ignored as they are by Eq and Hash.
+impl Ord for Polynomial {
+    fn cmp(&self, other: &Polynomial) -> Ordering {
+        self.degree().cmp(&other.degree()).then_with(|| {
+            self.significant_coefficients()
+                .cmp(other.significant_coefficients())
+        })
+    }
+}
+
+impl PartialOrd for Polynomial {
+    fn partial_cmp(&self, other: &Polynomial) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn polynomial_over(field: &Field, values: &[u64]) -> Polynomial {
+        Polynomial::new(
+            values
+                .iter()
+                .map(|v| FieldElement::new(BigInt::from(*v), field.clone()))
+                .collect(),
+        )
+    }
+
+    fn polynomial(values: &[u64]) -> Polynomial {
+        polynomial_over(&Field::main(), values)
+    }
+
+    fn elements(field: &Field, values: &[u64]) -> Vec<FieldElement> {
+        values
+            .iter()
+            .map(|v| FieldElement::new(BigInt::from(*v), field.clone()))
+            .collect()
+    }
+
+    // prod (x - r) over the given roots.
+    fn from_roots(field: &Field, roots: &[u64]) -> Polynomial {
+        roots
+            .iter()
+            .fold(polynomial_over(field, &[1]), |mut acc, r| {
+                let mut root = FieldElement::new(BigInt::from(*r), field.clone());
+                acc.mul(&mut Polynomial::new(vec![root.neg(), field.one()]))
+            })
+    }
+
+    fn product(factors: &mut [Polynomial]) -> Polynomial {
+        let field = factors[0].coefficients[0].field.clone();
+        factors
+            .iter_mut()
+            .fold(polynomial_over(&field, &[1]), |mut acc, f| acc.mul(f))
+    }
+
+    fn linear(root: &FieldElement) -> Polynomial {
+        Polynomial::new(vec![root.clone().neg(), root.field.one()])
+    }
+
+    #[test]
+    fn random_has_exact_degree() {
+        // Over F_2 every non-leading coefficient is zero half the time, so
+        // only the forced non-zero leading coefficient keeps the degree.
+        let mut rng = StdRng::seed_from_u64(2);
+        for field in [Field::new(BigInt::from(2)), Field::main()] {
+            for degree in 0..12 {
+                let f = Polynomial::random(degree, &field, &mut rng);
+                assert_eq!(f.degree(), Some(degree));
+                assert_eq!(f.coefficients.len(), degree + 1);
+            }
+        }
+
+        let draw = |seed| Polynomial::random(8, &Field::main(), &mut StdRng::seed_from_u64(seed));
+        assert_eq!(draw(42), draw(42));
+        assert_ne!(draw(42), draw(43));
+    }
+
+    #[test]
+    fn divide_by_a_linear_polynomial() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let field = Field::main();
+        let mut f = Polynomial::random(9, &field, &mut rng);
+        // 3x + 5 is not monic; the remainder is f(-5/3).
+        let mut denominator = polynomial(&[5, 3]);
+        let (mut quotient, remainder) = f.divide(&mut denominator).unwrap();
+        assert_eq!(quotient.degree(), Some(8));
+        assert_eq!(
+            quotient.mul(&mut denominator).add(&mut remainder.clone()),
+            f
+        );
+        let mut root = FieldElement::new(BigInt::from(5), field.clone())
+            .neg()
+            .div(&mut FieldElement::new(BigInt::from(3), field.clone()));
+        assert_eq!(remainder, Polynomial::new(vec![f.evaluate(&mut root)]));
+
+        // A multiple of the denominator leaves no remainder.
+        let mut multiple = denominator.mul(&mut f);
+        let (quotient, remainder) = multiple.divide(&mut denominator).unwrap();
+        assert_eq!(quotient, f);
+        assert!(remainder.is_zero());
+    }
+
+    #[test]
+    fn deep_quotient() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let field = Field::main();
+        let mut f = Polynomial::random(7, &field, &mut rng);
+        let mut z = field.random(&mut rng);
+        let mut quotient = f.deep_quotient(&mut z);
+        assert_eq!(quotient.degree(), Some(6));
+        let mut value = Polynomial::new(vec![f.evaluate(&mut z)]);
+        assert_eq!(quotient.mul(&mut linear(&z)).add(&mut value), f);
+
+        // Constants and zero have a zero quotient.
+        assert!(polynomial(&[9]).deep_quotient(&mut z).is_zero());
+        assert!(polynomial(&[]).deep_quotient(&mut z).is_zero());
+    }
+
+    #[test]
+    fn compose() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let field = Field::main();
+        let mut f = Polynomial::random(5, &field, &mut rng);
+        let mut g = Polynomial::random(3, &field, &mut rng);
+        let mut composed = f.compose(&mut g);
+        assert_eq!(composed.degree(), Some(15));
+        let mut x = field.random(&mut rng);
+        assert_eq!(
+            composed.evaluate(&mut x),
+            f.evaluate(&mut g.evaluate(&mut x))
+        );
+
+        assert_eq!(f.compose(&mut polynomial(&[0, 1])), f);
+        let mut two = FieldElement::new(BigInt::from(2), field.clone());
+        let constant = Polynomial::new(vec![f.evaluate(&mut two)]);
+        assert_eq!(f.compose(&mut polynomial(&[2])), constant);
+        assert!(polynomial(&[]).compose(&mut g).is_zero());
+    }
+
+    #[test]
+    fn derivative() {
+        // d/dx (4 + 3x + 2x^2 + x^3) = 3 + 4x + 3x^2
+        assert_eq!(
+            polynomial(&[4, 3, 2, 1]).derivative(),
+            polynomial(&[3, 4, 3])
+        );
+        assert!(polynomial(&[4]).derivative().is_zero());
+        assert!(polynomial(&[]).derivative().is_zero());
+
+        // In F_7 the derivative of x^7 vanishes.
+        let f7 = Field::new(BigInt::from(7));
+        assert!(polynomial_over(&f7, &[0, 0, 0, 0, 0, 0, 0, 1])
+            .derivative()
+            .is_zero());
+
+        let mut rng = StdRng::seed_from_u64(6);
+        let field = Field::main();
+        let mut f = Polynomial::random(6, &field, &mut rng);
+        let mut g = Polynomial::random(4, &field, &mut rng);
+        assert_eq!(
+            f.mul(&mut g).derivative(),
+            f.derivative()
+                .mul(&mut g)
+                .add(&mut f.mul(&mut g.derivative()))
+        );
+    }
+
+    #[test]
+    fn shift_and_reverse() {
+        let mut f = polynomial(&[1, 2, 0, 3]);
+        assert_eq!(f.shift(2), polynomial(&[0, 0, 1, 2, 0, 3]));
+        assert_eq!(f.shift(0), f);
+        assert!(polynomial(&[]).shift(3).is_zero());
+
+        assert_eq!(f.reverse(), polynomial(&[3, 0, 2, 1]));
+        assert_eq!(f.reverse().reverse(), f);
+        // Trailing zeros are not coefficients, leading zeros are dropped.
+        assert_eq!(polynomial(&[1, 2, 0, 3, 0]).reverse(), f.reverse());
+        assert_eq!(f.shift(2).reverse(), f.reverse());
+        assert!(polynomial(&[]).reverse().is_zero());
+
+        // x^deg * f(1/x)
+        let mut x = FieldElement::new(BigInt::from(11), Field::main());
+        let expected = x.pow(3).mul(&mut f.evaluate(&mut x.inverse()));
+        assert_eq!(f.reverse().evaluate(&mut x), expected);
+        // x^2 * f(x)
+        let expected = x.pow(2).mul(&mut f.evaluate(&mut x));
+        assert_eq!(f.shift(2).evaluate(&mut x), expected);
+    }
+
+    #[test]
+    fn scale() {
+        // f(2x) for f = 1 + x + x^2
+        let mut two = FieldElement::new(BigInt::from(2), Field::main());
+        assert_eq!(
+            polynomial(&[1, 1, 1]).scale(&mut two),
+            polynomial(&[1, 2, 4])
+        );
+
+        let mut rng = StdRng::seed_from_u64(8);
+        let field = Field::main();
+        let mut f = Polynomial::random(5, &field, &mut rng);
+        let (mut w, mut x) = (field.random(&mut rng), field.random(&mut rng));
+        let expected = f.evaluate(&mut w.clone().mul(&mut x));
+        assert_eq!(f.scale(&mut w).evaluate(&mut x), expected);
+    }
+
+    #[test]
+    fn xgcd_satisfies_bezout() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let field = Field::main();
+        let (a, b) = (field.random(&mut rng), field.random(&mut rng));
+        let mut f = linear(&a).mul(&mut linear(&b));
+        let mut g = linear(&a)
+            .mul(&mut linear(&field.one()))
+            .mul(&mut polynomial(&[5]));
+
+        let (mut s, mut t, gcd) = f.xgcd(&mut g);
+        assert_eq!(gcd, linear(&a));
+        assert_eq!(s.mul(&mut f).add(&mut t.mul(&mut g)), gcd);
+
+        let mut coprime = linear(&field.one());
+        assert_eq!(f.gcd(&mut coprime), polynomial(&[1]));
+    }
+
+    #[test]
+    fn gcd_with_zero() {
+        let mut g = polynomial(&[6, 4, 2]);
+        let monic = polynomial(&[3, 2, 1]);
+        assert_eq!(polynomial(&[]).gcd(&mut g), monic);
+        assert_eq!(g.gcd(&mut polynomial(&[])), monic);
+        assert!(polynomial(&[]).gcd(&mut polynomial(&[])).is_zero());
+
+        let (mut s, mut t, gcd) = polynomial(&[]).xgcd(&mut g);
+        assert_eq!(gcd, monic);
+        assert_eq!(s.mul(&mut polynomial(&[])).add(&mut t.mul(&mut g)), gcd);
+    }
+
+    #[test]
+    fn gcd_detects_repeated_factors() {
+        let field = Field::main();
+        let (a, b) = (
+            FieldElement::new(BigInt::from(11), field.clone()),
+            FieldElement::new(BigInt::from(12), field.clone()),
+        );
+        let mut f = linear(&a).mul(&mut linear(&b));
+        assert_eq!(f.clone().gcd(&mut f.derivative()).degree(), Some(0));
+        let mut repeated = f.mul(&mut linear(&a));
+        assert_eq!(repeated.clone().gcd(&mut repeated.derivative()), linear(&a));
+    }
+
+    #[test]
+    fn resultant() {
+        let mut rng = StdRng::seed_from_u64(8);
+        let field = Field::main();
+        let (mut a, mut b) = (field.random(&mut rng), field.random(&mut rng));
+        assert_eq!(linear(&a).resultant(&mut linear(&b)), a.sub(&mut b));
+
+        let mut f = linear(&a).mul(&mut linear(&b));
+        let mut shares_a = linear(&a).mul(&mut linear(&field.one()));
+        assert!(f.resultant(&mut shares_a).is_zero());
+        assert!(!f.resultant(&mut linear(&field.one())).is_zero());
+        assert!(f.resultant(&mut polynomial(&[])).is_zero());
+    }
+
+    #[test]
+    fn resultant_with_a_constant() {
+        // Res(f, c) = c^deg f, and Res(c, f) = c^deg f as well.
+        let mut f = polynomial(&[1, 2, 3, 4]);
+        let mut c = polynomial(&[5]);
+        let expected = FieldElement::new(BigInt::from(125), Field::main());
+        assert_eq!(f.resultant(&mut c), expected);
+        assert_eq!(c.resultant(&mut f), expected);
+        assert_eq!(c.resultant(&mut polynomial(&[7])), Field::main().one());
+    }
+
+    #[test]
+    fn roots_include_zero() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for p in [7u64, 13, 97] {
+            let field = Field::new(BigInt::from(p));
+            let mut f = from_roots(&field, &[0, 2, 5]);
+            assert_eq!(
+                f.roots(&mut rng),
+                Ok(elements(&field, &[0, 2, 5])),
+                "p = {p}"
+            );
+        }
+    }
+
+    #[test]
+    fn repeated_roots_are_reported_once() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let field = Field::new(BigInt::from(13));
+        let mut f = from_roots(&field, &[3, 3, 3, 4, 0, 0]);
+        assert_eq!(f.roots(&mut rng), Ok(elements(&field, &[0, 3, 4])));
+        assert!(f.factor(&mut rng).is_err());
+    }
+
+    #[test]
+    fn roots_in_the_main_field() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let field = Field::main();
+        let mut roots: Vec<FieldElement> = (0..4).map(|_| field.random(&mut rng)).collect();
+        roots.push(field.zero());
+        let mut f = roots.iter().fold(polynomial(&[1]), |mut acc, r| {
+            acc.mul(&mut Polynomial::new(vec![r.clone().neg(), field.one()]))
+        });
+        // x^2 - 3 has no roots: 3 is not a square modulo p.
+        f = f.mul(&mut polynomial(&[0, 0, 1]).sub(&mut polynomial(&[3])));
+        roots.sort();
+        assert_eq!(f.roots(&mut rng), Ok(roots));
+    }
+
+    #[test]
+    fn roots_of_constants_and_zero() {
+        let mut rng = StdRng::seed_from_u64(4);
+        assert_eq!(polynomial(&[5]).roots(&mut rng), Ok(Vec::new()));
+        assert!(polynomial(&[]).roots(&mut rng).is_err());
+        assert!(polynomial(&[]).factor(&mut rng).is_err());
+    }
+
+    #[test]
+    fn factor_splits_irreducible_quadratics_of_the_same_degree() {
+        // Over F_7 the squares are 1, 2 and 4, so x^2 + 1 and x^2 + 4 are
+        // irreducible, as is x^2 + 3 + x (discriminant 1 - 12 = 3).
+        let field = Field::new(BigInt::from(7));
+        let mut expected = vec![
+            polynomial_over(&field, &[2, 1]),
+            polynomial_over(&field, &[1, 0, 1]),
+            polynomial_over(&field, &[3, 1, 1]),
+            polynomial_over(&field, &[4, 0, 1]),
+        ];
+        let mut f = product(&mut expected);
+        for seed in 0..8 {
+            assert_eq!(
+                f.factor(&mut StdRng::seed_from_u64(seed)),
+                Ok(expected.clone())
+            );
+        }
+        let mut scaled = f.scale_coefficients(&mut FieldElement::new(BigInt::from(3), field));
+        assert_eq!(scaled.factor(&mut StdRng::seed_from_u64(5)), Ok(expected));
+    }
+
+    #[test]
+    fn factor_in_a_larger_prime_field() {
+        let mut rng = StdRng::seed_from_u64(6);
+        let field = Field::new(BigInt::from(97));
+        // -5 and -7 are not squares modulo 97.
+        let mut factors = vec![
+            polynomial_over(&field, &[0, 1]),
+            polynomial_over(&field, &[96, 1]),
+            polynomial_over(&field, &[5, 0, 1]),
+            polynomial_over(&field, &[7, 0, 1]),
+        ];
+        let mut f = product(&mut factors);
+        factors.sort();
+        assert_eq!(f.factor(&mut rng), Ok(factors));
+    }
+
+    #[test]
+    fn ordering_ignores_trailing_zeros() {
+        let mut padded = polynomial(&[5, 1]);
+        padded.coefficients.push(Field::main().zero());
+        assert_eq!(padded.cmp(&polynomial(&[5, 1])), Ordering::Equal);
+        assert!(polynomial(&[]) < polynomial(&[0, 1]));
+        assert!(polynomial(&[9, 9]) < polynomial(&[1, 0, 1]));
+        assert!(polynomial(&[1, 2]) < polynomial(&[2, 1]));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        for polynomial in [polynomial(&[]), polynomial(&[1, 0, 3])] {
+            let json = serde_json::to_string(&polynomial).unwrap();
+            assert_eq!(
+                serde_json::from_str::<Polynomial>(&json).unwrap(),
+                polynomial
+            );
+            let bytes = bincode::serialize(&polynomial).unwrap();
+            assert_eq!(
+                bincode::deserialize::<Polynomial>(&bytes).unwrap(),
+                polynomial
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_writes_the_modulus_once() {
+        let field = Field::main();
+        let p = field.p.clone();
+        let polynomial = Polynomial::new(
+            (1..=100)
+                .map(|i| FieldElement::new(p.clone() - BigInt::from(i), field.clone()))
+                .collect(),
+        );
+
+        let json = serde_json::to_string(&polynomial).unwrap();
+        assert_eq!(json.matches(&p.to_string()).count(), 1);
+
+        // Field: 8-byte length + 16 bytes; coefficients: 8-byte length,
+        // a width byte and 16 bytes per element; 1 byte for the Option tag.
+        let bytes = bincode::serialize(&polynomial).unwrap();
+        assert_eq!(bytes.len(), 1 + 8 + 16 + 8 + 1 + 100 * 16);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects_non_canonical_coefficients() {
+        let p = Field::main().p;
+        let json = format!(r#"{{"field":{{"p":"{p}"}},"coefficients":["1","{p}"]}}"#);
+        assert!(serde_json::from_str::<Polynomial>(&json).is_err());
+        assert!(
+            serde_json::from_str::<Polynomial>(r#"{"field":null,"coefficients":["1"]}"#).is_err()
+        );
+        assert!(
+            serde_json::from_str::<Polynomial>(r#"{"field":{"p":"0"},"coefficients":[]}"#).is_err()
+        );
+
+        let mut bytes = bincode::serialize(&polynomial(&[1, 2])).unwrap();
+        let last = bytes.len() - 16;
+        bytes[last..].copy_from_slice(&[0xff; 16]);
+        assert!(bincode::deserialize::<Polynomial>(&bytes).is_err());
     }
 }
@@ -0,0 +1,410 @@
+use crate::domain::*;
+use crate::fields::*;
+use crate::multivariate::*;
+use crate::polynomials::*;
+use num_bigint::{BigInt, BigUint};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
+
+#[derive(Clone, Debug)]
+pub struct RescuePrime {
+    pub field: Field,
+    pub m: usize,
+    pub capacity: usize,
+    pub n: usize,
+    pub security_level: usize,
+    pub alpha: u64,
+    pub alpha_inv: BigUint,
+    pub mds: Vec<Vec<FieldElement>>,
+    pub mds_inv: Vec<Vec<FieldElement>>,
+    pub round_constants: Vec<FieldElement>,
+}
+
+impl RescuePrime {
+    pub fn new() -> RescuePrime {
+        let field = Field::main();
+        let (m, capacity, n, security_level) = (2, 1, 27, 128);
+
+        let alpha = 3;
+        let p_minus_one = field.p.clone() - BigInt::from(1);
+        let (a, _, g) = xgcd(BigInt::from(alpha), p_minus_one.clone());
+        assert_eq!(g, BigInt::from(1), "alpha must be coprime to p - 1");
+        let alpha_inv = (((a % p_minus_one.clone()) + p_minus_one.clone()) % p_minus_one)
+            .to_biguint()
+            .unwrap();
+
+        let mds = RescuePrime::mds_matrix(&field, m);
+        let mds_inv = invert_matrix(&field, &mds);
+        let round_constants = RescuePrime::round_constants(&field, m, capacity, security_level, n);
+
+        RescuePrime {
+            field,
+            m,
+            capacity,
+            n,
+            security_level,
+            alpha,
+            alpha_inv,
+            mds,
+            mds_inv,
+            round_constants,
+        }
+    }
+
+    // Expands the Rescue-XLIX seed string with SHAKE256 into `2 * m * n` field
+    // elements, as in the Rescue-Prime specification.
+    pub fn round_constants(
+        field: &Field,
+        m: usize,
+        capacity: usize,
+        security_level: usize,
+        n: usize,
+    ) -> Vec<FieldElement> {
+        let bytes_per_int = field.p.bits().div_ceil(8) as usize + 1;
+        let seed = format!(
+            "Rescue-XLIX({},{},{},{})",
+            field.p, m, capacity, security_level
+        );
+
+        let mut hasher = Shake256::default();
+        hasher.update(seed.as_bytes());
+        let mut bytes = vec![0u8; bytes_per_int * 2 * m * n];
+        hasher.finalize_xof().read(&mut bytes);
+
+        bytes
+            .chunks(bytes_per_int)
+            .map(|chunk| {
+                FieldElement::new(
+                    BigInt::from_bytes_le(num_bigint::Sign::Plus, chunk) % field.p.clone(),
+                    field.clone(),
+                )
+            })
+            .collect()
+    }
+
+    // Takes the systematic form of the `m x 2m` Vandermonde matrix over the
+    // smallest primitive element; the MDS matrix is the transpose of its right
+    // half.
+    pub fn mds_matrix(field: &Field, m: usize) -> Vec<Vec<FieldElement>> {
+        let g = field.multiplicative_generator();
+        let mut vandermonde: Vec<Vec<FieldElement>> = (0..m)
+            .map(|i| (0..2 * m).map(|j| g.pow((i * j) as u64)).collect())
+            .collect();
+        echelon_form(&mut vandermonde);
+
+        (0..m)
+            .map(|i| (0..m).map(|j| vandermonde[j][m + i].clone()).collect())
+            .collect()
+    }
+
+    pub fn permutation(&self, state: &mut [FieldElement]) {
+        assert_eq!(state.len(), self.m);
+        for r in 0..self.n {
+            self.round(r, state);
+        }
+    }
+
+    fn round(&self, r: usize, state: &mut [FieldElement]) {
+        // forward half-round: S-box, MDS, constants
+        for s in state.iter_mut() {
+            *s = s.pow_vartime(&BigUint::from(self.alpha));
+        }
+        self.mds_mul(state);
+        for (j, s) in state.iter_mut().enumerate() {
+            *s = s.add(&mut self.round_constants[2 * r * self.m + j].clone());
+        }
+
+        // backward half-round: inverse S-box, MDS, constants
+        for s in state.iter_mut() {
+            *s = s.pow_vartime(&self.alpha_inv);
+        }
+        self.mds_mul(state);
+        for (j, s) in state.iter_mut().enumerate() {
+            *s = s.add(&mut self.round_constants[2 * r * self.m + self.m + j].clone());
+        }
+    }
+
+    pub fn hash(&self, input: &FieldElement) -> FieldElement {
+        let mut state = vec![self.field.zero(); self.m];
+        state[0] = input.clone();
+        self.permutation(&mut state);
+        state[0].clone()
+    }
+
+    // The execution trace of `hash(input)`: the state before the first round
+    // followed by the state after every round, `n + 1` rows of `m` registers.
+    pub fn trace(&self, input: &FieldElement) -> Vec<Vec<FieldElement>> {
+        let mut state = vec![self.field.zero(); self.m];
+        state[0] = input.clone();
+
+        let mut trace = vec![state.clone()];
+        for r in 0..self.n {
+            self.round(r, &mut state);
+            trace.push(state.clone());
+        }
+        trace
+    }
+
+    // `(cycle, register, value)` triples: the capacity starts out zero and the
+    // rate ends up holding the hash output.
+    pub fn boundary_constraints(&self, output: &FieldElement) -> Vec<(usize, usize, FieldElement)> {
+        vec![(0, 1, self.field.zero()), (self.n, 0, output.clone())]
+    }
+
+    // Interpolates the first- and second-half round constants of every register
+    // over `omicron^r`, lifted into the cycle-index variable.
+    pub fn round_constants_polynomials(
+        &self,
+        omicron: &FieldElement,
+    ) -> (Vec<MPolynomial>, Vec<MPolynomial>) {
+        let domain: Vec<FieldElement> = (0..self.n).map(|r| omicron.pow(r as u64)).collect();
+
+        let step_constants = |offset: usize| -> Vec<MPolynomial> {
+            (0..self.m)
+                .map(|i| {
+                    let mut values: Vec<FieldElement> = (0..self.n)
+                        .map(|r| self.round_constants[2 * r * self.m + offset + i].clone())
+                        .collect();
+                    let univariate = Polynomial::interpolate_domain(&domain, &mut values);
+                    MPolynomial::lift(&univariate, 0)
+                })
+                .collect()
+        };
+
+        let first_step_constants = step_constants(0);
+        let second_step_constants = step_constants(self.m);
+        (first_step_constants, second_step_constants)
+    }
+
+    // One round as `m` polynomials in `(cycle, previous state, next state)`.
+    // The inverse S-box is moved to the other side so every constraint has
+    // degree `alpha`: `MDS * prev^alpha + c1 = (MDS^-1 * (next - c2))^alpha`.
+    pub fn transition_constraints(&self, omicron: &FieldElement) -> Vec<MPolynomial> {
+        let (mut first_step_constants, mut second_step_constants) =
+            self.round_constants_polynomials(omicron);
+
+        let variables = MPolynomial::variables(1 + 2 * self.m, &self.field);
+        let previous_state = &variables[1..(1 + self.m)];
+        let next_state = &variables[(1 + self.m)..(1 + 2 * self.m)];
+
+        let mut next_minus_constants: Vec<MPolynomial> = next_state
+            .iter()
+            .zip(second_step_constants.iter_mut())
+            .map(|(state, constant)| state.clone().sub(constant))
+            .collect();
+
+        let mut air = Vec::new();
+        for (i, first_step_constant) in first_step_constants.iter_mut().enumerate() {
+            let mut lhs = MPolynomial::constant(self.field.zero());
+            for (mds, state) in self.mds[i].iter().zip(previous_state) {
+                lhs = lhs
+                    .add(&mut MPolynomial::constant(mds.clone()).mul(&mut state.pow(self.alpha)));
+            }
+            lhs = lhs.add(first_step_constant);
+
+            let mut rhs = MPolynomial::constant(self.field.zero());
+            for (mds_inv, state) in self.mds_inv[i].iter().zip(next_minus_constants.iter_mut()) {
+                rhs = rhs.add(&mut MPolynomial::constant(mds_inv.clone()).mul(state));
+            }
+            rhs = rhs.pow(self.alpha);
+
+            air.push(lhs.sub(&mut rhs));
+        }
+        air
+    }
+
+    // Divides every transition constraint, applied to the interpolated trace,
+    // by the zerofier of the cycles it must hold on: the first `n` elements
+    // of `domain`, i.e. all but its last `size - n`.
+    pub fn transition_quotients(
+        &self,
+        trace: &[Vec<FieldElement>],
+        domain: &EvaluationDomain,
+    ) -> Result<Vec<Polynomial>, &'static str> {
+        if domain.size < trace.len() || domain.offset != self.field.one() {
+            return Err("trace must fit in a subgroup domain");
+        }
+        let mut omicron = domain.generator.clone();
+        let rows: Vec<FieldElement> = domain.elements().take(trace.len()).collect();
+
+        let mut point = vec![Polynomial::new(
+            [self.field.zero(), self.field.one()].to_vec(),
+        )];
+        let mut trace_polynomials: Vec<Polynomial> = (0..self.m)
+            .map(|register| {
+                let mut column: Vec<FieldElement> =
+                    trace.iter().map(|row| row[register].clone()).collect();
+                Polynomial::interpolate_domain(&rows, &mut column)
+            })
+            .collect();
+        point.extend(trace_polynomials.iter().cloned());
+        point.extend(trace_polynomials.iter_mut().map(|p| p.scale(&mut omicron)));
+
+        let zerofier = domain.zerofier_except_last(domain.size - self.n);
+        self.transition_constraints(&omicron)
+            .iter()
+            .map(|constraint| zerofier.divide(&mut constraint.evaluate_symbolic(&point)))
+            .collect()
+    }
+
+    fn mds_mul(&self, state: &mut [FieldElement]) {
+        let old = state.to_vec();
+        for (i, s) in state.iter_mut().enumerate() {
+            let mut acc = self.field.zero();
+            for (j, o) in old.iter().enumerate() {
+                acc = acc.add(&mut self.mds[i][j].clone().mul(&mut o.clone()));
+            }
+            *s = acc;
+        }
+    }
+}
+
+impl Default for RescuePrime {
+    fn default() -> Self {
+        RescuePrime::new()
+    }
+}
+
+fn invert_matrix(field: &Field, matrix: &[Vec<FieldElement>]) -> Vec<Vec<FieldElement>> {
+    let n = matrix.len();
+    let mut augmented: Vec<Vec<FieldElement>> = matrix
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut row = row.clone();
+            row.extend((0..n).map(|j| if i == j { field.one() } else { field.zero() }));
+            row
+        })
+        .collect();
+    echelon_form(&mut augmented);
+    augmented.iter().map(|row| row[n..].to_vec()).collect()
+}
+
+// Brings `matrix` into reduced row echelon form in place.
+fn echelon_form(matrix: &mut [Vec<FieldElement>]) {
+    let rows = matrix.len();
+    let cols = matrix[0].len();
+    let mut pivot_row = 0;
+
+    for col in 0..cols {
+        if pivot_row == rows {
+            break;
+        }
+        let Some(pivot) = (pivot_row..rows).find(|&r| !matrix[r][col].is_zero()) else {
+            continue;
+        };
+        matrix.swap(pivot_row, pivot);
+
+        let mut inv = matrix[pivot_row][col].inverse();
+        for entry in matrix[pivot_row].iter_mut() {
+            *entry = entry.mul(&mut inv);
+        }
+        let normalized = matrix[pivot_row].clone();
+        for (r, row) in matrix.iter_mut().enumerate() {
+            if r == pivot_row || row[col].is_zero() {
+                continue;
+            }
+            let mut factor = row[col].clone();
+            for (entry, p) in row.iter_mut().zip(normalized.iter()) {
+                *entry = entry.sub(&mut p.clone().mul(&mut factor));
+            }
+        }
+        pivot_row += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(value: &str) -> FieldElement {
+        FieldElement::new(value.parse().unwrap(), Field::main())
+    }
+
+    // The reference vectors of the Rescue-Prime instance used by the
+    // Anatomy of a STARK tutorial.
+    #[test]
+    fn hash_matches_reference_vectors() {
+        let rp = RescuePrime::new();
+        assert_eq!(
+            rp.hash(&element("1")),
+            element("244180265933090377212304188905974087294")
+        );
+        assert_eq!(
+            rp.hash(&element("57322816861100832358702415967512842988")),
+            element("89633745865384635541695204788332415101")
+        );
+    }
+
+    // Every transition constraint evaluated at each cycle r of the trace.
+    fn transition_values(
+        air: &[MPolynomial],
+        omicron: &FieldElement,
+        trace: &[Vec<FieldElement>],
+    ) -> Vec<Vec<FieldElement>> {
+        (0..trace.len() - 1)
+            .map(|r| {
+                let mut point = vec![omicron.pow(r as u64)];
+                point.extend(trace[r].iter().cloned());
+                point.extend(trace[r + 1].iter().cloned());
+                air.iter().map(|c| c.evaluate(&mut point)).collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn constraints_hold_on_an_honest_trace() {
+        let rp = RescuePrime::new();
+        let input = element("57322816861100832358702415967512842988");
+        let output = rp.hash(&input);
+        let trace = rp.trace(&input);
+
+        assert_eq!(trace.len(), rp.n + 1);
+        assert!(trace.iter().all(|row| row.len() == rp.m));
+        assert_eq!(trace[0], vec![input, rp.field.zero()]);
+        for (cycle, register, value) in rp.boundary_constraints(&output) {
+            assert_eq!(trace[cycle][register], value);
+        }
+        let omicron = rp.field.primitive_nth_root(BigInt::from(32));
+        let air = rp.transition_constraints(&omicron);
+        for values in transition_values(&air, &omicron, &trace) {
+            assert!(values.iter().all(|v| v.is_zero()));
+        }
+    }
+
+    #[test]
+    fn tampered_register_violates_a_constraint() {
+        let rp = RescuePrime::new();
+        let input = element("1");
+        let output = rp.hash(&input);
+        let honest = rp.trace(&input);
+        let omicron = rp.field.primitive_nth_root(BigInt::from(32));
+        let air = rp.transition_constraints(&omicron);
+
+        for (row, register) in [(0, 1), (5, 0), (5, 1), (rp.n, 0)] {
+            let mut trace = honest.clone();
+            trace[row][register] = trace[row][register].add(&mut rp.field.one());
+            let boundary_holds = rp
+                .boundary_constraints(&output)
+                .iter()
+                .all(|(cycle, register, value)| trace[*cycle][*register] == *value);
+            let transitions = transition_values(&air, &omicron, &trace);
+            let broken: Vec<usize> = (0..rp.n)
+                .filter(|r| transitions[*r].iter().any(|v| !v.is_zero()))
+                .collect();
+
+            // The register enters the transitions into and out of its row.
+            let expected: Vec<usize> = [row.checked_sub(1), Some(row).filter(|r| *r < rp.n)]
+                .into_iter()
+                .flatten()
+                .collect();
+            assert_eq!(broken, expected, "row {row} register {register}");
+            assert_eq!(
+                boundary_holds,
+                !rp.boundary_constraints(&output)
+                    .iter()
+                    .any(|(c, r, _)| (*c, *r) == (row, register)),
+            );
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use crate::domain::*;
+use crate::fields::*;
+use crate::polynomials::*;
+use num_bigint::BigInt;
+
+// Reed-Solomon codes over multiplicative subgroups. A message of k field
+// elements is read as the coefficients of a polynomial of degree below k,
+// and its codeword is that polynomial evaluated over the subgroup of size
+// k * expansion_factor, exactly the low-degree extension of a STARK trace.
+
+// The subgroup of order length, which exists only when length divides p - 1.
+pub fn codeword_domain(field: &Field, length: usize) -> Result<EvaluationDomain, &'static str> {
+    if length == 0 {
+        return Err("codeword length must be positive");
+    }
+    let p_minus_one: BigInt = field.p.clone() - BigInt::from(1);
+    if p_minus_one % BigInt::from(length) != BigInt::ZERO {
+        return Err("codeword length does not divide p - 1");
+    }
+    Ok(EvaluationDomain::new(field, length))
+}
+
+pub fn encode(
+    message: &[FieldElement],
+    expansion_factor: usize,
+) -> Result<Vec<FieldElement>, &'static str> {
+    let Some(first) = message.first() else {
+        return Err("cannot encode an empty message");
+    };
+    let domain = codeword_domain(&first.field, message.len() * expansion_factor)?;
+    Ok(domain.evaluate(&Polynomial::new(message.to_vec())))
+}
+
+// Number of positions in which two words differ.
+pub fn distance(a: &[FieldElement], b: &[FieldElement]) -> usize {
+    assert_eq!(a.len(), b.len());
+    a.iter().zip(b.iter()).filter(|(x, y)| x != y).count()
+}
+
+// Recovers the message from any message_length known symbols; None marks an
+// erased position. Every known symbol has to agree with the interpolant.
+pub fn decode_erasures(
+    received: &[Option<FieldElement>],
+    message_length: usize,
+) -> Result<Vec<FieldElement>, &'static str> {
+    let Some(field) = received.iter().flatten().next().map(|x| x.field.clone()) else {
+        return Err("every symbol is erased");
+    };
+    let domain = codeword_domain(&field, received.len())?;
+    let (points, mut values): (Vec<FieldElement>, Vec<FieldElement>) = received
+        .iter()
+        .enumerate()
+        .filter_map(|(i, x)| x.clone().map(|x| (domain.element(i), x)))
+        .unzip();
+    if points.len() < message_length {
+        return Err("too many erasures to decode");
+    }
+
+    let polynomial = Polynomial::interpolate_domain(&points, &mut values);
+    message_from_polynomial(polynomial, message_length, &field)
+}
+
+// Gao's decoder: with g0 the domain's zerofier and g1 the interpolant of the
+// received word, run the extended Euclidean algorithm on (g0, g1) until the
+// remainder r = u * g0 + v * g1 drops below degree (n + k) / 2. Then r / v
+// is the message polynomial whenever at most (n - k) / 2 symbols are wrong.
+pub fn decode(
+    received: &[FieldElement],
+    message_length: usize,
+) -> Result<Vec<FieldElement>, &'static str> {
+    let Some(field) = received.first().map(|x| x.field.clone()) else {
+        return Err("cannot decode an empty word");
+    };
+    let n = received.len();
+    if message_length == 0 || message_length > n {
+        return Err("message length must be between 1 and the codeword length");
+    }
+    let domain = codeword_domain(&field, n)?;
+
+    let (mut old_r, mut r) = (
+        Polynomial::zerofier_domain(&domain),
+        domain.interpolate(received),
+    );
+    let mut old_v = Polynomial::new(Vec::new());
+    let mut v = Polynomial::new([field.one()].to_vec());
+    while r.degree().is_some_and(|d| 2 * d >= n + message_length) {
+        let (mut quotient, remainder) = old_r.divide(&mut r)?;
+        old_r = std::mem::replace(&mut r, remainder);
+        let next_v = old_v.sub(&mut quotient.mul(&mut v));
+        old_v = std::mem::replace(&mut v, next_v);
+    }
+
+    let (polynomial, remainder) = r.divide(&mut v)?;
+    if !remainder.is_zero() {
+        return Err("too many errors to decode");
+    }
+    message_from_polynomial(polynomial, message_length, &field)
+}
+
+fn message_from_polynomial(
+    polynomial: Polynomial,
+    message_length: usize,
+    field: &Field,
+) -> Result<Vec<FieldElement>, &'static str> {
+    if polynomial.degree().is_some_and(|d| d >= message_length) {
+        return Err("received word is not close to a codeword");
+    }
+    let mut message = polynomial.coefficients;
+    message.resize(message_length, field.zero());
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(field: &Field, k: usize) -> Vec<FieldElement> {
+        (1..=k)
+            .map(|i| FieldElement::new(BigInt::from(i), field.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn rejects_lengths_without_a_subgroup() {
+        // p - 1 = 407 * 2^119 = 11 * 37 * 2^119, so there is no subgroup of
+        // order 20, 12 or 6.
+        let field = Field::main();
+        assert!(encode(&message(&field, 5), 4).is_err());
+        assert!(decode(&message(&field, 12), 4).is_err());
+        assert!(decode_erasures(&vec![Some(field.one()); 6], 2).is_err());
+    }
+
+    #[test]
+    fn non_power_of_two_length_round_trips() {
+        // Goldilocks p - 1 = 2^32 * 3 * 5 * 17 * 257 * 65537 has order 20.
+        let field = Field::new(BigInt::from(0xffff_ffff_0000_0001u64));
+        let message = message(&field, 5);
+        let mut codeword = encode(&message, 4).unwrap();
+        codeword[3] = codeword[3].add(&mut field.one());
+        assert_eq!(decode(&codeword, 5), Ok(message));
+    }
+
+    #[test]
+    fn rejects_empty_words() {
+        assert!(encode(&[], 4).is_err());
+        assert!(decode(&[], 1).is_err());
+        assert!(decode_erasures(&[], 1).is_err());
+    }
+}
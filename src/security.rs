@@ -0,0 +1,146 @@
+use crate::fields::*;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SecurityLevel {
+    pub conjectured: u32,
+    pub proven: u32,
+}
+
+// The level StarkParameters::new has to reach, in bits, under either the
+// ethSTARK conjecture or the proven bound. The two can differ by more than
+// a factor of two, so callers have to pick one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SecurityTarget {
+    Conjectured(u32),
+    Proven(u32),
+}
+
+impl SecurityLevel {
+    pub fn meets(&self, target: SecurityTarget) -> bool {
+        match target {
+            SecurityTarget::Conjectured(bits) => self.conjectured >= bits,
+            SecurityTarget::Proven(bits) => self.proven >= bits,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StarkParameters {
+    pub field: Field,
+    pub expansion_factor: usize,
+    pub num_queries: usize,
+    pub grinding_bits: usize,
+    pub constraint_degree: usize,
+    pub trace_length: usize,
+}
+
+impl StarkParameters {
+    pub fn new(
+        field: Field,
+        expansion_factor: usize,
+        num_queries: usize,
+        grinding_bits: usize,
+        constraint_degree: usize,
+        trace_length: usize,
+        security_target: SecurityTarget,
+    ) -> Result<StarkParameters, &'static str> {
+        if expansion_factor < 2 || !expansion_factor.is_power_of_two() {
+            return Err("expansion factor must be a power of two of at least 2");
+        }
+        if num_queries == 0 || constraint_degree == 0 || trace_length == 0 {
+            return Err("queries, constraint degree and trace length must be positive");
+        }
+        let parameters = StarkParameters {
+            field,
+            expansion_factor,
+            num_queries,
+            grinding_bits,
+            constraint_degree,
+            trace_length,
+        };
+        if !parameters.security_level().meets(security_target) {
+            return Err("parameters do not reach the requested security level");
+        }
+        Ok(parameters)
+    }
+
+    // The evaluation domain covers the composition polynomial's degree
+    // (trace length times constraint degree, rounded up to a power of two)
+    // times the expansion factor.
+    pub fn fri_domain_length(&self) -> usize {
+        (self.trace_length * self.constraint_degree).next_power_of_two() * self.expansion_factor
+    }
+
+    pub fn security_level(&self) -> SecurityLevel {
+        SecurityLevel {
+            conjectured: self.conjectured_security(),
+            proven: self.proven_security(),
+        }
+    }
+
+    // ethSTARK conjecture: every query contributes log2(expansion factor)
+    // bits, grinding adds its bits on top, and the field must be large enough
+    // that a random out-of-domain point is unlikely to hit the domain.
+    fn conjectured_security(&self) -> u32 {
+        let field_security = self.field_bits() - (self.fri_domain_length() as f64).log2();
+        let query_security = self.num_queries as f64 * (self.expansion_factor as f64).log2()
+            + self.grinding_bits as f64;
+        (field_security.min(query_security) - 1.0).max(0.0).floor() as u32
+    }
+
+    // Proven bound in the list-decoding regime up to the Johnson radius
+    // (BCIKS20, as summarized in eprint 2022/1216), maximized over the
+    // proximity parameter m >= 3.
+    fn proven_security(&self) -> u32 {
+        (3..=64)
+            .map(|m| self.proven_security_for_m(m as f64))
+            .fold(0.0_f64, f64::max)
+            .max(0.0)
+            .floor() as u32
+    }
+
+    fn proven_security_for_m(&self, m: f64) -> f64 {
+        let field_bits = self.field_bits();
+        let rho = 1.0 / self.expansion_factor as f64;
+        let domain = self.fri_domain_length() as f64;
+
+        // FRI commit phase: (m + 1/2)^7 / (3 rho^(3/2)) * |D|^2 / |F|
+        let commit =
+            field_bits - ((m + 0.5).powi(7) / (3.0 * rho.powf(1.5)) * domain * domain).log2();
+
+        // FRI query phase: each query passes with probability
+        // sqrt(rho) * (1 + 1/(2m)) against a word outside the Johnson radius.
+        let per_query = -(rho.sqrt() * (1.0 + 0.5 / m)).log2();
+        let query = self.num_queries as f64 * per_query + self.grinding_bits as f64;
+
+        // DEEP-ALI: every codeword in the list of size (m + 1/2) / sqrt(rho)
+        // can agree with a constraint of degree d * T at d * T points.
+        let list_size = (m + 0.5) / rho.sqrt();
+        let constraint = (self.constraint_degree * self.trace_length) as f64;
+        let deep = field_bits - (list_size * list_size * constraint).log2();
+
+        commit.min(query).min(deep) - 1.0
+    }
+
+    fn field_bits(&self) -> f64 {
+        (self.field.p.bits() - 1) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_is_checked_against_the_chosen_model() {
+        let parameters = |target| StarkParameters::new(Field::main(), 4, 64, 0, 3, 28, target);
+        let level = parameters(SecurityTarget::Conjectured(0))
+            .unwrap()
+            .security_level();
+        assert!(level.proven < 100 && level.conjectured >= 100);
+
+        assert!(parameters(SecurityTarget::Conjectured(100)).is_ok());
+        assert!(parameters(SecurityTarget::Proven(100)).is_err());
+        assert!(parameters(SecurityTarget::Proven(level.proven)).is_ok());
+    }
+}
@@ -0,0 +1,308 @@
+use crate::fields::*;
+use crate::polynomials::*;
+use num_bigint::{BigInt, Sign};
+
+// Every encoding starts with a version byte. Field elements are 16 bytes
+// little-endian, so only fields with p < 2^128 can be encoded; sequences are
+// prefixed with a little-endian u32 length.
+pub const FORMAT_VERSION: u8 = 1;
+pub const FIELD_ELEMENT_BYTES: usize = 16;
+pub const MAX_LENGTH: usize = 1 << 24;
+
+pub struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    pub fn new() -> Writer {
+        Writer {
+            bytes: vec![FORMAT_VERSION],
+        }
+    }
+
+    pub fn write_length(&mut self, length: usize) -> Result<(), &'static str> {
+        if length > MAX_LENGTH {
+            return Err("sequence too long to encode");
+        }
+        self.bytes.extend((length as u32).to_le_bytes());
+        Ok(())
+    }
+
+    pub fn write_field_element(&mut self, element: &FieldElement) -> Result<(), &'static str> {
+        check_field(&element.field)?;
+        let (sign, mut bytes) = element.value.to_bytes_le();
+        if sign == Sign::Minus || element.value >= element.field.p {
+            return Err("non-canonical field element");
+        }
+        bytes.resize(FIELD_ELEMENT_BYTES, 0);
+        self.bytes.extend(bytes);
+        Ok(())
+    }
+
+    pub fn write_field_elements(&mut self, elements: &[FieldElement]) -> Result<(), &'static str> {
+        self.write_length(elements.len())?;
+        elements
+            .iter()
+            .try_for_each(|element| self.write_field_element(element))
+    }
+
+    pub fn write_polynomial(&mut self, polynomial: &Polynomial) -> Result<(), &'static str> {
+        self.write_field_elements(polynomial.significant_coefficients())
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+impl Default for Writer {
+    fn default() -> Self {
+        Writer::new()
+    }
+}
+
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    field: Field,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8], field: Field) -> Result<Reader<'a>, &'static str> {
+        check_field(&field)?;
+        match bytes.first() {
+            None => Err("empty encoding"),
+            Some(&FORMAT_VERSION) => Ok(Reader {
+                bytes,
+                position: 1,
+                field,
+            }),
+            Some(_) => Err("unsupported format version"),
+        }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], &'static str> {
+        if self.bytes.len() - self.position < n {
+            return Err("unexpected end of encoding");
+        }
+        let slice = &self.bytes[self.position..self.position + n];
+        self.position += n;
+        Ok(slice)
+    }
+
+    // Rejects lengths that could not possibly be backed by the remaining
+    // bytes, so a corrupt prefix cannot trigger a huge allocation.
+    pub fn read_length(&mut self, item_bytes: usize) -> Result<usize, &'static str> {
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(self.take(4)?);
+        let length = u32::from_le_bytes(buf) as usize;
+        if length > MAX_LENGTH || length * item_bytes > self.bytes.len() - self.position {
+            return Err("sequence length exceeds encoding");
+        }
+        Ok(length)
+    }
+
+    pub fn read_field_element(&mut self) -> Result<FieldElement, &'static str> {
+        let value = BigInt::from_bytes_le(Sign::Plus, self.take(FIELD_ELEMENT_BYTES)?);
+        if value >= self.field.p {
+            return Err("non-canonical field element");
+        }
+        Ok(FieldElement::new(value, self.field.clone()))
+    }
+
+    pub fn read_field_elements(&mut self) -> Result<Vec<FieldElement>, &'static str> {
+        let length = self.read_length(FIELD_ELEMENT_BYTES)?;
+        (0..length).map(|_| self.read_field_element()).collect()
+    }
+
+    pub fn read_polynomial(&mut self) -> Result<Polynomial, &'static str> {
+        let coefficients = self.read_field_elements()?;
+        if coefficients.last().is_some_and(|c| c.is_zero()) {
+            return Err("non-canonical polynomial");
+        }
+        Ok(Polynomial::new(coefficients))
+    }
+
+    pub fn finish(self) -> Result<(), &'static str> {
+        if self.position != self.bytes.len() {
+            return Err("trailing bytes after encoding");
+        }
+        Ok(())
+    }
+}
+
+fn check_field(field: &Field) -> Result<(), &'static str> {
+    if field.byte_len() > FIELD_ELEMENT_BYTES {
+        return Err("field elements do not fit in the encoding");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(value: u64) -> FieldElement {
+        FieldElement::new(BigInt::from(value), Field::main())
+    }
+
+    fn encode() -> Vec<u8> {
+        let mut writer = Writer::new();
+        writer.write_field_element(&element(7)).unwrap();
+        writer
+            .write_polynomial(&Polynomial::new(vec![element(1), element(2), element(3)]))
+            .unwrap();
+        writer.finish()
+    }
+
+    fn decode(bytes: &[u8]) -> Result<(FieldElement, Polynomial), &'static str> {
+        let mut reader = Reader::new(bytes, Field::main())?;
+        let decoded = (reader.read_field_element()?, reader.read_polynomial()?);
+        reader.finish()?;
+        Ok(decoded)
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes = encode();
+        assert_eq!(
+            bytes.len(),
+            1 + FIELD_ELEMENT_BYTES + 4 + 3 * FIELD_ELEMENT_BYTES
+        );
+        assert_eq!(
+            decode(&bytes),
+            Ok((
+                element(7),
+                Polynomial::new(vec![element(1), element(2), element(3)])
+            ))
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_version() {
+        let mut bytes = encode();
+        bytes[0] = FORMAT_VERSION + 1;
+        assert!(matches!(decode(&bytes), Err("unsupported format version")));
+        assert!(matches!(decode(&[]), Err("empty encoding")));
+    }
+
+    #[test]
+    fn rejects_non_canonical_values() {
+        let mut bytes = encode();
+        bytes[1..1 + FIELD_ELEMENT_BYTES].copy_from_slice(&[0xff; FIELD_ELEMENT_BYTES]);
+        assert!(matches!(decode(&bytes), Err("non-canonical field element")));
+
+        // p itself is the smallest non-canonical value.
+        let (_, p) = Field::main().p.to_bytes_le();
+        let mut bytes = encode();
+        bytes[1..1 + p.len()].copy_from_slice(&p);
+        assert!(matches!(decode(&bytes), Err("non-canonical field element")));
+    }
+
+    #[test]
+    fn rejects_trailing_zero_coefficient() {
+        let mut writer = Writer::new();
+        writer.write_field_element(&element(7)).unwrap();
+        writer
+            .write_field_elements(&[element(1), element(0)])
+            .unwrap();
+        assert!(matches!(
+            decode(&writer.finish()),
+            Err("non-canonical polynomial")
+        ));
+    }
+
+    #[test]
+    fn rejects_oversized_length() {
+        let mut bytes = encode();
+        let length = 1 + FIELD_ELEMENT_BYTES;
+        bytes[length..length + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            decode(&bytes),
+            Err("sequence length exceeds encoding")
+        ));
+        bytes[length..length + 4].copy_from_slice(&4u32.to_le_bytes());
+        assert!(matches!(
+            decode(&bytes),
+            Err("sequence length exceeds encoding")
+        ));
+    }
+
+    #[test]
+    fn rejects_truncated_input() {
+        let bytes = encode();
+        for end in [1, FIELD_ELEMENT_BYTES, 1 + FIELD_ELEMENT_BYTES + 2] {
+            assert!(matches!(
+                decode(&bytes[..end]),
+                Err("unexpected end of encoding")
+            ));
+        }
+        assert!(matches!(
+            decode(&bytes[..bytes.len() - 1]),
+            Err("sequence length exceeds encoding")
+        ));
+    }
+
+    #[test]
+    fn rejects_trailing_bytes() {
+        let mut bytes = encode();
+        bytes.push(0);
+        assert!(matches!(
+            decode(&bytes),
+            Err("trailing bytes after encoding")
+        ));
+    }
+
+    #[test]
+    fn padded_polynomial_round_trips() {
+        let mut padded = Polynomial::new(vec![element(1), element(2)]);
+        padded.coefficients.extend([element(0), element(0)]);
+        let mut writer = Writer::new();
+        writer.write_polynomial(&padded).unwrap();
+        let bytes = writer.finish();
+
+        let mut reader = Reader::new(&bytes, Field::main()).unwrap();
+        assert_eq!(reader.read_polynomial(), Ok(padded));
+        assert_eq!(reader.finish(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_fields_wider_than_the_encoding() {
+        // 2^130 + 169 (not prime, but only the width matters here)
+        let wide = Field::new((BigInt::from(1) << 130) + BigInt::from(169));
+        assert!(matches!(
+            Reader::new(&encode(), wide.clone()),
+            Err("field elements do not fit in the encoding")
+        ));
+        let mut writer = Writer::new();
+        assert!(matches!(
+            writer.write_field_element(&FieldElement::new(BigInt::from(1), wide)),
+            Err("field elements do not fit in the encoding")
+        ));
+        // 2^128 - 159 is the largest prime that still fits.
+        let narrow = Field::new((BigInt::from(1) << 128) - BigInt::from(159));
+        let largest = FieldElement::new(narrow.p.clone() - 1, narrow.clone());
+        writer.write_field_element(&largest).unwrap();
+        let bytes = writer.finish();
+        let mut reader = Reader::new(&bytes, narrow).unwrap();
+        assert_eq!(reader.read_field_element(), Ok(largest));
+    }
+
+    #[test]
+    fn writer_rejects_what_the_reader_would() {
+        let mut writer = Writer::new();
+        assert!(matches!(
+            writer.write_length(MAX_LENGTH + 1),
+            Err("sequence too long to encode")
+        ));
+        let out_of_range = FieldElement {
+            value: Field::main().p,
+            field: Field::main(),
+        };
+        assert!(matches!(
+            writer.write_field_element(&out_of_range),
+            Err("non-canonical field element")
+        ));
+        assert_eq!(writer.finish(), vec![FORMAT_VERSION]);
+    }
+}
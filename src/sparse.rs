@@ -0,0 +1,204 @@
+use crate::fields::*;
+use crate::polynomials::*;
+use num_bigint::BigInt;
+use std::collections::BTreeMap;
+
+// Terms keyed by degree, e.g. x^1024 - 1 is {0: -1, 1024: 1}. Zero
+// coefficients are never stored, so the zero polynomial is the empty map and
+// the last key is the degree.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SparsePolynomial {
+    pub terms: BTreeMap<usize, FieldElement>,
+}
+
+impl SparsePolynomial {
+    pub fn new(mut terms: BTreeMap<usize, FieldElement>) -> SparsePolynomial {
+        terms.retain(|_, c| !c.is_zero());
+        SparsePolynomial { terms }
+    }
+
+    pub fn monomial(degree: usize, coefficient: FieldElement) -> SparsePolynomial {
+        SparsePolynomial::new(BTreeMap::from([(degree, coefficient)]))
+    }
+
+    pub fn degree(&self) -> Option<usize> {
+        self.terms.keys().next_back().copied()
+    }
+
+    pub fn leading_coefficient(&self) -> Option<FieldElement> {
+        self.terms.values().next_back().cloned()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn neg(&self) -> SparsePolynomial {
+        SparsePolynomial {
+            terms: self
+                .terms
+                .iter()
+                .map(|(d, c)| (*d, c.clone().neg()))
+                .collect(),
+        }
+    }
+
+    pub fn add(&self, other: &SparsePolynomial) -> SparsePolynomial {
+        let mut terms = self.terms.clone();
+        for (d, c) in other.terms.iter() {
+            let sum = match terms.get_mut(d) {
+                Some(acc) => acc.add(&mut c.clone()),
+                None => c.clone(),
+            };
+            terms.insert(*d, sum);
+        }
+        SparsePolynomial::new(terms)
+    }
+
+    pub fn sub(&self, other: &SparsePolynomial) -> SparsePolynomial {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &SparsePolynomial) -> SparsePolynomial {
+        let mut terms: BTreeMap<usize, FieldElement> = BTreeMap::new();
+        for (d0, c0) in self.terms.iter() {
+            for (d1, c1) in other.terms.iter() {
+                let mut product = c0.clone().mul(&mut c1.clone());
+                let sum = match terms.get_mut(&(d0 + d1)) {
+                    Some(acc) => acc.add(&mut product),
+                    None => product,
+                };
+                terms.insert(d0 + d1, sum);
+            }
+        }
+        SparsePolynomial::new(terms)
+    }
+
+    pub fn derivative(&self) -> SparsePolynomial {
+        SparsePolynomial::new(
+            self.terms
+                .iter()
+                .filter(|(d, _)| **d > 0)
+                .map(|(d, c)| {
+                    let mut degree = FieldElement::new(BigInt::from(*d), c.field.clone());
+                    (d - 1, c.clone().mul(&mut degree))
+                })
+                .collect(),
+        )
+    }
+
+    // Walks the terms in increasing degree, raising x only by the gap to the
+    // next term: O(t log n) for t terms of degree at most n.
+    pub fn evaluate(&self, point: &FieldElement) -> FieldElement {
+        let mut acc = point.field.zero();
+        let mut power = point.field.one();
+        let mut power_degree = 0;
+        for (d, c) in self.terms.iter() {
+            power = power.mul(&mut point.pow((d - power_degree) as u64));
+            power_degree = *d;
+            acc = acc.add(&mut c.clone().mul(&mut power));
+        }
+        acc
+    }
+}
+
+impl From<&Polynomial> for SparsePolynomial {
+    fn from(polynomial: &Polynomial) -> SparsePolynomial {
+        SparsePolynomial::new(
+            polynomial
+                .coefficients
+                .iter()
+                .cloned()
+                .enumerate()
+                .collect(),
+        )
+    }
+}
+
+impl From<&SparsePolynomial> for Polynomial {
+    fn from(sparse: &SparsePolynomial) -> Polynomial {
+        let (Some(degree), Some(leading)) = (sparse.degree(), sparse.leading_coefficient()) else {
+            return Polynomial::new(Vec::new());
+        };
+        let mut coefficients = vec![leading.field.zero(); degree + 1];
+        for (d, c) in sparse.terms.iter() {
+            coefficients[*d] = c.clone();
+        }
+        Polynomial::new(coefficients)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn element(value: u64) -> FieldElement {
+        FieldElement::new(BigInt::from(value), Field::main())
+    }
+
+    // x^64 - 5 x^3 + 7
+    fn sparse() -> SparsePolynomial {
+        SparsePolynomial::monomial(64, element(1))
+            .sub(&SparsePolynomial::monomial(3, element(5)))
+            .add(&SparsePolynomial::monomial(0, element(7)))
+    }
+
+    #[test]
+    fn dense_round_trip() {
+        let sparse = sparse();
+        assert_eq!(sparse.terms.len(), 3);
+        assert_eq!(sparse.degree(), Some(64));
+        assert_eq!(sparse.leading_coefficient(), Some(element(1)));
+        let dense = Polynomial::from(&sparse);
+        assert_eq!(dense.degree(), Some(64));
+        assert_eq!(SparsePolynomial::from(&dense), sparse);
+
+        let zero = SparsePolynomial::new(BTreeMap::from([(4, element(0))]));
+        assert!(zero.is_zero() && zero.degree().is_none());
+        assert!(Polynomial::from(&zero).is_zero());
+        assert!(sparse.sub(&sparse).is_zero());
+    }
+
+    #[test]
+    fn arithmetic_matches_dense() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let field = Field::main();
+        let (a, b) = (
+            sparse(),
+            SparsePolynomial::from(&Polynomial::random(6, &field, &mut rng)),
+        );
+        let (mut dense_a, mut dense_b) = (Polynomial::from(&a), Polynomial::from(&b));
+
+        assert_eq!(Polynomial::from(&a.add(&b)), dense_a.add(&mut dense_b));
+        assert_eq!(Polynomial::from(&a.sub(&b)), dense_a.sub(&mut dense_b));
+        assert_eq!(Polynomial::from(&a.mul(&b)), dense_a.mul(&mut dense_b));
+        assert_eq!(Polynomial::from(&a.neg()), dense_a.neg());
+        assert_eq!(Polynomial::from(&a.derivative()), dense_a.derivative());
+        for _ in 0..4 {
+            let mut x = field.random(&mut rng);
+            assert_eq!(a.evaluate(&x), dense_a.evaluate(&mut x));
+        }
+        assert_eq!(a.evaluate(&field.zero()), element(7));
+    }
+
+    #[test]
+    fn divide_by_sparse_matches_divide() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let field = Field::main();
+        let mut divisor = Polynomial::from(&sparse());
+        for degree in [0, 63, 64, 100, 200] {
+            let mut f = Polynomial::random(degree, &field, &mut rng);
+            assert_eq!(f.divide_by_sparse(&sparse()), f.divide(&mut divisor));
+        }
+        // A non-monic divisor.
+        let scaled = sparse().mul(&SparsePolynomial::monomial(0, element(3)));
+        let mut f = Polynomial::random(90, &field, &mut rng);
+        assert_eq!(
+            f.divide_by_sparse(&scaled),
+            f.divide(&mut Polynomial::from(&scaled))
+        );
+        assert!(f.divide_by_sparse(&SparsePolynomial::default()).is_err());
+    }
+}